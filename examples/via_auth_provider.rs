@@ -9,11 +9,11 @@ fn main() {
     let long_url = "https://doc.rust-lang.org/std/";
 
     let us = UrlShortener::new().unwrap();
-    let key = "MY_API_KEY";
+    let token = "MY_TOKEN";
     let short_url = us.generate(
         long_url,
-        &Provider::GooGl {
-            api_key: key.to_owned(),
+        &Provider::BitLy {
+            token: token.to_owned(),
         },
     );
 
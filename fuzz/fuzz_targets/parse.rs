@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use urlshortener::providers::{self, Provider};
+
+fuzz_target!(|data: &[u8]| {
+    let res = String::from_utf8_lossy(data);
+    let key = || "KEY".to_owned();
+    let authenticated = [
+        Provider::BitLy { token: key() },
+        Provider::Cuttly { api_key: key() },
+        Provider::Kutt {
+            api_key: key(),
+            host: None,
+        },
+        Provider::ShortIo {
+            api_key: key(),
+            domain: "example.com".to_owned(),
+        },
+        Provider::T2m { api_key: key() },
+    ];
+
+    for provider in providers::PROVIDERS.iter().chain(authenticated.iter()) {
+        let _ = providers::parse(&res, provider);
+        let _ = providers::parse_expand(&res, provider);
+        let _ = providers::parse_bulk(&res, provider);
+        let _ = providers::parse_stats(&res, provider);
+        let _ = providers::parse_list_links(&res, provider);
+        let _ = providers::parse_delete(&res, provider);
+    }
+    let _ = providers::parse_shrtcode_links(&res);
+    let _ = providers::parse_t2m_link(&res);
+});
@@ -0,0 +1,149 @@
+use crate::providers::Provider;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The outcomes of the shortening attempts made with a provider.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct ProviderStats {
+    /// The number of attempts.
+    pub attempts: u64,
+    /// The number of attempts which produced a short URL.
+    pub successes: u64,
+    /// The total time the attempts took, in milliseconds.
+    pub total_millis: u64,
+}
+
+impl ProviderStats {
+    /// Records the outcome of an attempt.
+    pub fn record(&mut self, success: bool, elapsed: Duration) {
+        self.attempts += 1;
+        if success {
+            self.successes += 1;
+        }
+        self.total_millis = self.total_millis.saturating_add(elapsed.as_millis() as u64);
+    }
+
+    /// Returns the estimated probability of the next attempt to succeed. The
+    /// estimation starts at one half for a provider which has never been
+    /// tried.
+    pub fn success_rate(&self) -> f64 {
+        (self.successes as f64 + 1.0) / (self.attempts as f64 + 2.0)
+    }
+
+    /// Returns the mean duration of an attempt, or `None` if the provider
+    /// has never been tried.
+    pub fn mean_latency(&self) -> Option<Duration> {
+        self.total_millis
+            .checked_div(self.attempts)
+            .map(Duration::from_millis)
+    }
+}
+
+/// A storage which keeps the provider statistics across runs, by provider
+/// name (see `Provider::to_name`).
+pub trait StatsStorage {
+    /// Loads the statistics saved previously.
+    fn load(&self) -> io::Result<HashMap<String, ProviderStats>>;
+    /// Saves the statistics, replacing the ones saved previously.
+    fn save(&self, stats: &HashMap<String, ProviderStats>) -> io::Result<()>;
+}
+
+/// Keeps the provider statistics in a text file, one provider per line.
+#[derive(Debug, Clone)]
+pub struct FileStatsStorage {
+    path: PathBuf,
+}
+
+impl FileStatsStorage {
+    /// Creates new `FileStatsStorage` keeping the statistics in the file.
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileStatsStorage {
+        FileStatsStorage { path: path.into() }
+    }
+}
+
+impl StatsStorage for FileStatsStorage {
+    fn load(&self) -> io::Result<HashMap<String, ProviderStats>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let stats = text
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let name = fields.next()?.to_owned();
+                let mut number = || fields.next()?.parse().ok();
+                let stats = ProviderStats {
+                    attempts: number()?,
+                    successes: number()?,
+                    total_millis: number()?,
+                };
+                Some((name, stats))
+            })
+            .collect();
+        Ok(stats)
+    }
+
+    fn save(&self, stats: &HashMap<String, ProviderStats>) -> io::Result<()> {
+        let text = stats
+            .iter()
+            .map(|(name, s)| {
+                format!(
+                    "{} {} {} {}\n",
+                    name, s.attempts, s.successes, s.total_millis
+                )
+            })
+            .collect::<String>();
+        fs::write(&self.path, text)
+    }
+}
+
+/// Orders the providers by their statistics: the most likely to succeed
+/// first, the faster first among the equally reliable ones. The providers
+/// with the same statistics keep their order.
+pub fn rank(providers: &mut [Provider], stats: &HashMap<String, ProviderStats>) {
+    let key = |provider: &Provider| {
+        let stats = stats.get(provider.to_name()).copied().unwrap_or_default();
+        (stats.success_rate(), stats.mean_latency())
+    };
+
+    providers.sort_by(|a, b| {
+        let (a_rate, a_latency) = key(a);
+        let (b_rate, b_latency) = key(b);
+        b_rate
+            .partial_cmp(&a_rate)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| match (a_latency, b_latency) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => std::cmp::Ordering::Equal,
+            })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_by_success() {
+        let mut stats = HashMap::new();
+        let mut failing = ProviderStats::default();
+        failing.record(false, Duration::from_millis(100));
+        stats.insert("is.gd".to_owned(), failing);
+        let mut working = ProviderStats::default();
+        working.record(true, Duration::from_millis(300));
+        stats.insert("bam.bz".to_owned(), working);
+
+        let mut providers = [Provider::IsGd, Provider::VGd, Provider::BamBz];
+        rank(&mut providers, &stats);
+        assert_eq!(
+            providers.iter().map(Provider::to_name).collect::<Vec<_>>(),
+            ["bam.bz", "v.gd", "is.gd"]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::providers::ProviderError;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+
+/// The outcome of shortening a single URL of a batch.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BatchRecord {
+    /// The URL to shorten.
+    pub long_url: String,
+    /// The name of the provider used (see `Provider::to_name`).
+    pub provider: String,
+    /// The short URL, or the error which occured.
+    pub result: Result<String, ProviderError>,
+}
+
+impl BatchRecord {
+    /// Creates new `BatchRecord`.
+    pub fn new<S: Into<String>>(
+        long_url: S,
+        provider: &str,
+        result: Result<String, ProviderError>,
+    ) -> BatchRecord {
+        BatchRecord {
+            long_url: long_url.into(),
+            provider: provider.to_owned(),
+            result,
+        }
+    }
+
+    /// Returns the record as a JSON object with the `long`, `short`,
+    /// `provider` and `error` fields, like the columns of `to_csv`. Either
+    /// the `short` or the `error` field is `null`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let (short, error) = match self.result {
+            Ok(ref short_url) => (Some(short_url.as_str()), None),
+            Err(e) => (None, Some(format!("{:?}", e))),
+        };
+        serde_json::json!({
+            "long": self.long_url,
+            "short": short,
+            "provider": self.provider,
+            "error": error,
+        })
+    }
+}
+
+/// Reads the URLs to shorten from the first column of the CSV data. The
+/// empty lines are skipped, as is the first line when it is a header, which
+/// is when its first column is not a URL (it has no scheme).
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::batch;
+///
+/// let csv = "url,campaign\nhttps://rust-lang.org,docs\n\"https://example.com/?a=1,2\",ads\n";
+/// let urls = batch::from_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(urls, ["https://rust-lang.org", "https://example.com/?a=1,2"]);
+/// ```
+pub fn from_csv<R: Read>(reader: R) -> io::Result<Vec<String>> {
+    let mut urls = Vec::new();
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let url = first_field(line.trim_end_matches('\r'));
+        if url.is_empty() || (i == 0 && !url.contains(':')) {
+            continue;
+        }
+        urls.push(url);
+    }
+    Ok(urls)
+}
+
+/// Writes the records as CSV data with the `long,short,provider,error`
+/// columns, the header included. Either the `short` or the `error` column is
+/// empty.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::{batch::{self, BatchRecord}, providers::ProviderError};
+///
+/// let records = [
+///     BatchRecord::new("https://rust-lang.org", "is.gd", Ok("https://is.gd/a".to_owned())),
+///     BatchRecord::new("not a url", "is.gd", Err(ProviderError::InvalidUrl)),
+/// ];
+/// let mut csv = Vec::new();
+/// batch::to_csv(&mut csv, &records).unwrap();
+/// assert_eq!(
+///     String::from_utf8(csv).unwrap(),
+///     "long,short,provider,error\n\
+///      https://rust-lang.org,https://is.gd/a,is.gd,\n\
+///      not a url,,is.gd,InvalidUrl\n"
+/// );
+/// ```
+pub fn to_csv<W: Write>(mut writer: W, records: &[BatchRecord]) -> io::Result<()> {
+    writeln!(writer, "long,short,provider,error")?;
+    for record in records {
+        let (short, error) = match record.result {
+            Ok(ref short_url) => (short_url.clone(), String::new()),
+            Err(e) => (String::new(), format!("{:?}", e)),
+        };
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            escape(&record.long_url),
+            escape(&short),
+            escape(&record.provider),
+            escape(&error)
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the record as a single line of JSON (see `BatchRecord::to_json`),
+/// so that the records may be streamed as they complete in the JSON Lines
+/// format.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::batch::{self, BatchRecord};
+///
+/// let record = BatchRecord::new("https://rust-lang.org", "is.gd", Ok("https://is.gd/a".to_owned()));
+/// let mut jsonl = Vec::new();
+/// batch::write_jsonl(&mut jsonl, &record).unwrap();
+/// assert_eq!(
+///     String::from_utf8(jsonl).unwrap(),
+///     "{\"error\":null,\"long\":\"https://rust-lang.org\",\"provider\":\"is.gd\",\"short\":\"https://is.gd/a\"}\n"
+/// );
+/// ```
+pub fn write_jsonl<W: Write>(mut writer: W, record: &BatchRecord) -> io::Result<()> {
+    writeln!(writer, "{}", record.to_json())?;
+    writer.flush()
+}
+
+/// The progress of a batch job persisted to a file, so that an interrupted
+/// job may resume where it left off instead of shortening the same URLs
+/// again.
+///
+/// Only the URLs which were shortened successfully are considered done: the
+/// failed ones are attempted again when the job resumes. The file holds one
+/// JSON record per line (see `write_jsonl`) and is appended to as the job
+/// progresses.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::batch::{BatchRecord, Checkpoint};
+///
+/// let path = std::env::temp_dir().join("urlshortener-checkpoint-doctest.jsonl");
+/// # let _ = std::fs::remove_file(&path);
+/// let mut checkpoint = Checkpoint::open(&path).unwrap();
+/// let record = BatchRecord::new("https://rust-lang.org", "is.gd", Ok("https://is.gd/a".to_owned()));
+/// checkpoint.record(&record).unwrap();
+///
+/// let resumed = Checkpoint::open(&path).unwrap();
+/// assert_eq!(resumed.get("https://rust-lang.org"), Some(&record));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    done: HashMap<String, BatchRecord>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint file, loading the records saved by the previous
+    /// runs. The file is created when the first record is saved. A line
+    /// which can't be read, for example one written partially when the job
+    /// was interrupted, is skipped.
+    pub fn open<P: Into<PathBuf>>(path: P) -> io::Result<Checkpoint> {
+        let path = path.into();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let done = text
+            .lines()
+            .filter_map(|line| {
+                let json: serde_json::Value = serde_json::from_str(line).ok()?;
+                let field = |name| json.get(name)?.as_str().map(String::from);
+                let record = BatchRecord {
+                    long_url: field("long")?,
+                    provider: field("provider")?,
+                    result: Ok(field("short")?),
+                };
+                Some((record.long_url.clone(), record))
+            })
+            .collect();
+        Ok(Checkpoint { path, done })
+    }
+
+    /// Returns the record of the URL if it is done.
+    pub fn get(&self, long_url: &str) -> Option<&BatchRecord> {
+        self.done.get(long_url)
+    }
+
+    /// Returns the number of the URLs which are done.
+    pub fn len(&self) -> usize {
+        self.done.len()
+    }
+
+    /// Returns whether no URL is done yet.
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty()
+    }
+
+    /// Saves the record if it is successful. The failed records are not
+    /// saved, so that their URLs are attempted again.
+    pub fn record(&mut self, record: &BatchRecord) -> io::Result<()> {
+        if record.result.is_err() {
+            return Ok(());
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        write_jsonl(file, record)?;
+        self.done.insert(record.long_url.clone(), record.clone());
+        Ok(())
+    }
+}
+
+/// Returns the first field of the CSV line, unquoted.
+fn first_field(line: &str) -> String {
+    let line = line.trim_start();
+    let mut chars = line.chars().peekable();
+
+    if chars.peek() != Some(&'"') {
+        return line.split(',').next().unwrap_or("").trim().to_owned();
+    }
+
+    chars.next();
+    let mut field = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => break,
+            c => field.push(c),
+        }
+    }
+    field
+}
+
+/// Quotes the CSV field if needed.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
@@ -1,11 +1,273 @@
-use crate::providers::{self, parse, request, ProviderError};
+use crate::adaptive::{self, ProviderStats, StatsStorage};
+use crate::batch::{BatchRecord, Checkpoint};
+use crate::credentials::CredentialProvider;
+use crate::expand::{self, ChainEnd, ExpandedUrl, Hop, RedirectChain};
+use crate::links::{parse_timestamp, Link, LinkPage};
+use crate::options::{OptionKind, ShortenOptions};
+use crate::providers::{
+    self, bulk_request, delete_request, expand_request, list_links_request, parse_bulk,
+    parse_delete, parse_expand, parse_list_links, parse_stats, parse_with_options,
+    request_with_credentials, request_with_options, stats_request, ProviderError, Quota,
+};
+use crate::request::{Request, UserAgent};
+use crate::stats::{LinkStats, StatsPeriod};
 use reqwest::blocking::{Client, ClientBuilder};
-use std::time::Duration;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_TYPE, LOCATION, REFRESH, RETRY_AFTER,
+    USER_AGENT,
+};
+use reqwest::redirect;
+use reqwest::StatusCode;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::Read;
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use url::Url;
+
+/// The timeout of the clients built by a `UrlShortener` created with
+/// `with_client`, whose own timeout is not known.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The longest cooldown a provider may ask for with the `Retry-After` header.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A set of the same provider configured with different credentials, for
+/// example several `BitLy` tokens, pooling their rate limits.
+///
+/// The rotation is shared between the threads, so the requests are spread
+/// across the credentials in a round-robin manner.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::{
+///     client::{ProviderRotation, UrlShortener},
+///     options::ShortenOptions,
+///     providers::Provider,
+/// };
+///
+/// let us = UrlShortener::new().unwrap();
+/// let rotation = ProviderRotation::new(vec![
+///     Provider::BitLy { token: "MY_FIRST_TOKEN".to_owned() },
+///     Provider::BitLy { token: "MY_SECOND_TOKEN".to_owned() },
+/// ]);
+/// let options = ShortenOptions::default();
+/// let _short_url = us.generate_rotating("https://rust-lang.org", &rotation, &options);
+/// ```
+#[derive(Debug)]
+pub struct ProviderRotation {
+    providers: Vec<providers::Provider>,
+    next: AtomicUsize,
+}
+
+impl ProviderRotation {
+    /// Creates new `ProviderRotation` over the providers.
+    pub fn new(providers: Vec<providers::Provider>) -> ProviderRotation {
+        ProviderRotation {
+            providers,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the providers of the rotation starting with the next one to
+    /// use, and advances the rotation.
+    fn next_round(&self) -> impl Iterator<Item = &providers::Provider> {
+        let len = self.providers.len();
+        let start = if len == 0 {
+            0
+        } else {
+            self.next.fetch_add(1, Ordering::Relaxed) % len
+        };
+        self.providers[start..]
+            .iter()
+            .chain(self.providers[..start].iter())
+    }
+}
+
+/// A short URL along with the details of the provider response.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ShortenedUrl {
+    /// The short URL.
+    pub short_url: String,
+    /// The rate limit the provider reported in the response headers, if any.
+    pub rate_limit: Option<RateLimit>,
+    /// The identifier of the call: the one given in the options or the one
+    /// generated by the client (see `UrlShortener::with_correlation_ids`).
+    pub correlation_id: Option<String>,
+    /// How long the provider call took.
+    pub timing: Timing,
+}
+
+/// How long a provider call took. The name resolution and connection times
+/// are not reported separately by the http client, so they are part of the
+/// time to the first byte.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Timing {
+    /// The time from sending the request until the response headers were
+    /// received.
+    pub first_byte: Duration,
+    /// The time from sending the request until the response was read and
+    /// parsed.
+    pub total: Duration,
+}
+
+/// The details of a provider response besides its text.
+struct Received {
+    rate_limit: Option<RateLimit>,
+    first_byte: Duration,
+}
+
+/// The rate limit reported by a provider in the `X-RateLimit-*` or
+/// `RateLimit-*` response headers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RateLimit {
+    /// The number of requests allowed in the current window.
+    pub limit: Option<u64>,
+    /// The number of requests left in the current window.
+    pub remaining: Option<u64>,
+    /// When the window resets, as reported by the provider: either the
+    /// number of seconds left or the unix timestamp.
+    pub reset: Option<u64>,
+}
+
+impl RateLimit {
+    /// Reads the rate limit from the response headers, returns `None` if
+    /// there are no rate limit headers.
+    fn from_headers(headers: &HeaderMap) -> Option<RateLimit> {
+        let header = |name: &str| {
+            [
+                format!("x-ratelimit-{}", name),
+                format!("ratelimit-{}", name),
+            ]
+            .iter()
+            .filter_map(|name| headers.get(name.as_str()))
+            .find_map(|value| value.to_str().ok()?.trim().parse().ok())
+        };
+        let rate_limit = RateLimit {
+            limit: header("limit"),
+            remaining: header("remaining"),
+            reset: header("reset"),
+        };
+
+        if rate_limit.limit.is_none()
+            && rate_limit.remaining.is_none()
+            && rate_limit.reset.is_none()
+        {
+            None
+        } else {
+            Some(rate_limit)
+        }
+    }
+}
 
 /// Url shortener: the way to retrieve a short url.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct UrlShortener {
     client: Client,
+    redirect_client: Option<Client>,
+    timeout: Option<Duration>,
+    transports: Vec<(String, Client)>,
+    default_headers: HeaderMap,
+    provider_headers: HashMap<String, HeaderMap>,
+    user_agent: Option<String>,
+    user_agents: HashMap<String, String>,
+    compression: bool,
+    correlation_ids: bool,
+    destination_check: bool,
+    base_url: Option<String>,
+    signer: Option<Arc<Signer>>,
+    hooks: Hooks,
+    validators: Vec<(String, Arc<Validator>)>,
+    quotas: HashMap<String, Quota>,
+    cooldown: Duration,
+    deadline: Option<Duration>,
+    cooldowns: Arc<Mutex<HashMap<providers::Provider, Instant>>>,
+    adaptive: Option<Arc<Adaptive>>,
+}
+
+/// The provider statistics collected by the client and their storage.
+struct Adaptive {
+    storage: Box<dyn StatsStorage + Send + Sync>,
+    stats: Mutex<HashMap<String, ProviderStats>>,
+}
+
+/// A callback which checks the short URL parsed from a provider response.
+type Validator = dyn Fn(&str) -> bool + Send + Sync;
+
+/// The callbacks observing the requests sent by the client.
+#[derive(Clone, Default)]
+struct Hooks {
+    on_request: Option<Arc<RequestHook>>,
+    on_response: Option<Arc<ResponseHook>>,
+    on_error: Option<Arc<ErrorHook>>,
+}
+
+type RequestHook = dyn Fn(&str, &Request) + Send + Sync;
+type ResponseHook = dyn Fn(&str, u16, Duration) + Send + Sync;
+type ErrorHook = dyn Fn(&str, ProviderError, Duration) + Send + Sync;
+
+/// A callback which signs the requests to a provider before they are sent.
+type Signer = dyn Fn(&providers::Provider, &mut Request) + Send + Sync;
+
+impl fmt::Debug for UrlShortener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UrlShortener")
+            .field("client", &self.client)
+            .field("redirect_client", &self.redirect_client)
+            .field("timeout", &self.timeout)
+            .field("transports", &self.transports)
+            // The headers may hold credentials, only their names are shown.
+            .field(
+                "default_headers",
+                &self.default_headers.keys().collect::<Vec<_>>(),
+            )
+            .field(
+                "provider_headers",
+                &self
+                    .provider_headers
+                    .iter()
+                    .map(|(name, headers)| (name, headers.keys().collect::<Vec<_>>()))
+                    .collect::<HashMap<_, _>>(),
+            )
+            .field("user_agent", &self.user_agent)
+            .field("user_agents", &self.user_agents)
+            .field("compression", &self.compression)
+            .field("correlation_ids", &self.correlation_ids)
+            .field("destination_check", &self.destination_check)
+            .field("base_url", &self.base_url)
+            .field("signer", &self.signer.as_ref().map(|_| "Fn"))
+            .field("on_request", &self.hooks.on_request.as_ref().map(|_| "Fn"))
+            .field(
+                "on_response",
+                &self.hooks.on_response.as_ref().map(|_| "Fn"),
+            )
+            .field("on_error", &self.hooks.on_error.as_ref().map(|_| "Fn"))
+            .field(
+                "validators",
+                &self
+                    .validators
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("quotas", &self.quotas)
+            .field("cooldown", &self.cooldown)
+            .field("deadline", &self.deadline)
+            .field("cooldowns", &self.cooldowns)
+            .field(
+                "adaptive",
+                &self
+                    .adaptive
+                    .as_ref()
+                    .map(|a| a.stats.lock().map(|s| s.clone())),
+            )
+            .finish()
+    }
 }
 
 impl UrlShortener {
@@ -16,11 +278,521 @@ impl UrlShortener {
 
     /// Creates new `UrlShortener` with custom read timeout.
     pub fn with_timeout(seconds: u64) -> Result<UrlShortener, reqwest::Error> {
+        let timeout = Duration::from_secs(seconds);
+        let client = ClientBuilder::new().timeout(timeout).build()?;
+        let redirect_client = ClientBuilder::new()
+            .timeout(timeout)
+            .redirect(redirect::Policy::none())
+            .build()?;
+
+        Ok(UrlShortener::from_parts(client, Some(timeout)).with_redirect_client(redirect_client))
+    }
+
+    /// Creates new `UrlShortener` sending the requests with the given client,
+    /// so that its proxy, TLS and connection pool settings are reused.
+    ///
+    /// The timeout of the client is not known, so the provider timeouts are
+    /// not scaled by their tiers (see `Provider::timeout_tier`), the client
+    /// timeout applies to all of them. The clients created for the Unix
+    /// socket transports (see `with_unix_socket`) use the default (3 seconds)
+    /// timeout.
+    ///
+    /// The client follows the redirects, so it can't be used to expand the
+    /// short URLs: give the one to use to `with_redirect_client`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::client::UrlShortener;
+    ///
+    /// let client = reqwest::blocking::Client::builder()
+    ///     .proxy(reqwest::Proxy::all("http://proxy.local:3128").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// let us = UrlShortener::with_client(client);
+    /// ```
+    pub fn with_client(client: Client) -> UrlShortener {
+        UrlShortener::from_parts(client, None)
+    }
+
+    fn from_parts(client: Client, timeout: Option<Duration>) -> UrlShortener {
+        UrlShortener {
+            client,
+            redirect_client: None,
+            timeout,
+            transports: Vec::new(),
+            default_headers: HeaderMap::new(),
+            provider_headers: HashMap::new(),
+            user_agent: None,
+            user_agents: HashMap::new(),
+            compression: true,
+            correlation_ids: false,
+            destination_check: false,
+            base_url: None,
+            signer: None,
+            hooks: Hooks::default(),
+            validators: Vec::new(),
+            quotas: HashMap::new(),
+            cooldown: Duration::from_secs(60),
+            deadline: None,
+            cooldowns: Arc::new(Mutex::new(HashMap::new())),
+            adaptive: None,
+        }
+    }
+
+    /// Sets the client following the redirects one by one in `expand_chain`
+    /// and `expand`, which must be built with `redirect::Policy::none()`, for
+    /// example with the proxy and TLS settings of the client given to
+    /// `with_client`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use reqwest::{blocking::Client, redirect, Proxy};
+    /// use urlshortener::client::UrlShortener;
+    ///
+    /// let proxy = Proxy::all("http://proxy.local:3128").unwrap();
+    /// let client = Client::builder().proxy(proxy.clone()).build().unwrap();
+    /// let redirect_client = Client::builder()
+    ///     .proxy(proxy)
+    ///     .redirect(redirect::Policy::none())
+    ///     .build()
+    ///     .unwrap();
+    /// let us = UrlShortener::with_client(client).with_redirect_client(redirect_client);
+    /// ```
+    pub fn with_redirect_client(mut self, client: Client) -> UrlShortener {
+        self.redirect_client = Some(client);
+        self
+    }
+
+    /// Sets the callback which is called with the provider name and the
+    /// request before every request is sent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::client::UrlShortener;
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .on_request(|provider, req| println!("{}: {}", provider, req.url))
+    ///     .on_response(|provider, status, elapsed| {
+    ///         println!("{}: {} in {:?}", provider, status, elapsed)
+    ///     })
+    ///     .on_error(|provider, error, elapsed| {
+    ///         println!("{}: {} in {:?}", provider, error, elapsed)
+    ///     });
+    /// ```
+    pub fn on_request<F>(mut self, hook: F) -> UrlShortener
+    where
+        F: Fn(&str, &Request) + Send + Sync + 'static,
+    {
+        self.hooks.on_request = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the callback which is called with the provider name, the HTTP
+    /// status and the time elapsed since the request was sent, whenever a
+    /// provider responds.
+    pub fn on_response<F>(mut self, hook: F) -> UrlShortener
+    where
+        F: Fn(&str, u16, Duration) + Send + Sync + 'static,
+    {
+        self.hooks.on_response = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the callback which is called with the provider name, the error
+    /// and the time elapsed since the request was sent, whenever a request
+    /// fails or its response can not be parsed.
+    pub fn on_error<F>(mut self, hook: F) -> UrlShortener
+    where
+        F: Fn(&str, ProviderError, Duration) + Send + Sync + 'static,
+    {
+        self.hooks.on_error = Some(Arc::new(hook));
+        self
+    }
+
+    /// Adds the callback which checks the short URLs parsed from the responses
+    /// of the provider with the given name (see `Provider::to_name`). The
+    /// short URLs the callback rejects are reported as
+    /// `ProviderError::Deserialize`, for example when a provider responds
+    /// with its home page or an error text instead of a short URL.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_validator("rlu.ru", |short_url| short_url.starts_with("http://rlu.ru/"));
+    /// let _short_url = us.generate("https://rust-lang.org", &Provider::Rlu);
+    /// ```
+    pub fn with_validator<F>(mut self, provider_name: &str, validator: F) -> UrlShortener
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.validators
+            .push((provider_name.to_owned(), Arc::new(validator)));
+        self
+    }
+
+    /// Sets the quota of the provider with the given name (see
+    /// `Provider::to_name`), overriding its default one (see
+    /// `Provider::default_quota`), for example for a paid plan with higher
+    /// limits or a conservative cap for a shared IP address.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use urlshortener::{client::UrlShortener, providers::Quota};
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_quota("bit.ly", Quota::new(1000, Duration::from_secs(60)))
+    ///     .with_quota("is.gd", Quota::new(50, Duration::from_secs(3600)));
+    /// ```
+    pub fn with_quota(mut self, provider_name: &str, quota: Quota) -> UrlShortener {
+        self.quotas.insert(provider_name.to_owned(), quota);
+        self
+    }
+
+    /// Sets how long a provider is cooling down after it reported being
+    /// rate limited or unavailable, unless it tells how long to wait with
+    /// the `Retry-After` header. The default is one minute.
+    ///
+    /// The multi-provider strategies (`try_generate`, `generate_adaptive`,
+    /// `generate_rotating` and `generate_scheduled`) move on to the next
+    /// provider straight away when a provider fails this way, and do not
+    /// pick it again until it has cooled down. The single-provider calls are
+    /// sent regardless.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> UrlShortener {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets the time budget of the multi-provider strategies
+    /// (`try_generate`, `generate_adaptive` and `generate_rotating`, and
+    /// `generate_scheduled` for each URL): no new provider attempt is started
+    /// once it is spent, and the attempts are given the time left at most.
+    /// The error of the last attempt is returned then. There is no budget by
+    /// default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions};
+    ///
+    /// let us = UrlShortener::new().unwrap().with_deadline(Duration::from_secs(5));
+    /// let res = us.generate_adaptive("https://rust-lang.org", &ShortenOptions::default());
+    /// ```
+    pub fn with_deadline(mut self, deadline: Duration) -> UrlShortener {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns whether the provider is cooling down after it reported being
+    /// rate limited or unavailable (see `with_cooldown`).
+    pub fn is_cooling_down(&self, provider: &providers::Provider) -> bool {
+        self.cooldown_until(provider).is_some()
+    }
+
+    /// Enables the adaptive provider ordering: the client loads the provider
+    /// statistics from the storage, keeps them up to date with the outcome of
+    /// every shortening attempt, and `generate_adaptive` tries the providers
+    /// which are the most likely to succeed on this network first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{adaptive::FileStatsStorage, client::UrlShortener, options::ShortenOptions};
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_adaptive_ordering(FileStatsStorage::new("providers.stats"))
+    ///     .unwrap();
+    /// let _short_url = us.generate_adaptive("https://rust-lang.org", &ShortenOptions::default());
+    /// ```
+    pub fn with_adaptive_ordering<S>(mut self, storage: S) -> std::io::Result<UrlShortener>
+    where
+        S: StatsStorage + Send + Sync + 'static,
+    {
+        let stats = storage.load()?;
+        self.adaptive = Some(Arc::new(Adaptive {
+            storage: Box::new(storage),
+            stats: Mutex::new(stats),
+        }));
+        Ok(self)
+    }
+
+    /// Returns the providers of the `PROVIDERS` list, ordered by the collected
+    /// statistics if the adaptive ordering is enabled.
+    pub fn ranked_providers(&self) -> Vec<providers::Provider> {
+        let mut ranked = providers::PROVIDERS.to_vec();
+        if let Some(ref adaptive) = self.adaptive {
+            if let Ok(stats) = adaptive.stats.lock() {
+                adaptive::rank(&mut ranked, &stats);
+            }
+        }
+        ranked
+    }
+
+    /// Saves the collected provider statistics to the storage, if the
+    /// adaptive ordering is enabled.
+    pub fn save_stats(&self) -> std::io::Result<()> {
+        match self.adaptive {
+            Some(ref adaptive) => {
+                let stats = adaptive.stats.lock().map(|s| s.clone()).unwrap_or_default();
+                adaptive.storage.save(&stats)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Sends the requests to the URLs starting with `base_url` over the Unix
+    /// domain socket instead of TCP, for example to reach a self-hosted
+    /// provider behind a local reverse proxy socket. The requests to other
+    /// providers are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_unix_socket("http://kutt.local", "/run/nginx/kutt.sock")
+    ///     .unwrap();
+    /// let provider = Provider::Kutt {
+    ///     api_key: "MY_API_KEY".to_owned(),
+    ///     host: Some("http://kutt.local".to_owned()),
+    /// };
+    /// let _short_url = us.generate("https://rust-lang.org", &provider);
+    /// ```
+    #[cfg(unix)]
+    pub fn with_unix_socket<P: AsRef<Path>>(
+        mut self,
+        base_url: &str,
+        socket: P,
+    ) -> Result<UrlShortener, reqwest::Error> {
         let client = ClientBuilder::new()
-            .timeout(Duration::from_secs(seconds))
+            .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+            .unix_socket(socket.as_ref())
             .build()?;
 
-        Ok(UrlShortener { client })
+        self.transports.push((base_url.to_owned(), client));
+        Ok(self)
+    }
+
+    /// Sets the headers added to every request. The headers set by the
+    /// providers take precedence over them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use reqwest::header::HeaderMap;
+    /// use urlshortener::client::UrlShortener;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("X-Request-Source", "my-app".parse().unwrap());
+    /// let us = UrlShortener::new().unwrap().with_default_headers(headers);
+    /// ```
+    pub fn with_default_headers(mut self, headers: HeaderMap) -> UrlShortener {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Adds the headers to every request to the provider with the given name
+    /// (see `Provider::to_name`), for example the credentials of an access
+    /// proxy in front of a self hosted instance. Unlike the default headers,
+    /// they take precedence over the headers set by the provider.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use reqwest::header::HeaderMap;
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let provider = Provider::Kutt {
+    ///     api_key: "MY_API_KEY".to_owned(),
+    ///     host: Some("https://kutt.example.com".to_owned()),
+    /// };
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("CF-Access-Client-Id", "MY_CLIENT_ID".parse().unwrap());
+    /// headers.insert("CF-Access-Client-Secret", "MY_CLIENT_SECRET".parse().unwrap());
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_provider_headers(provider.to_name(), headers);
+    /// ```
+    pub fn with_provider_headers(
+        mut self,
+        provider_name: &str,
+        headers: HeaderMap,
+    ) -> UrlShortener {
+        self.provider_headers
+            .entry(provider_name.to_owned())
+            .or_default()
+            .extend(headers);
+        self
+    }
+
+    /// Sets the user agent of the requests, so that the traffic may be
+    /// identified by the providers. The providers which are only usable with
+    /// a browser user agent keep theirs, see `with_provider_user_agent` to
+    /// override them as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::client::UrlShortener;
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_user_agent("my-app/1.0 (+https://example.com/bot)");
+    /// ```
+    pub fn with_user_agent<S: Into<String>>(mut self, user_agent: S) -> UrlShortener {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the user agent of the requests to the provider with the given
+    /// name (see `Provider::to_name`), overriding both the one set with
+    /// `with_user_agent` and the one the provider requires.
+    pub fn with_provider_user_agent<S: Into<String>>(
+        mut self,
+        provider_name: &str,
+        user_agent: S,
+    ) -> UrlShortener {
+        self.user_agents
+            .insert(provider_name.to_owned(), user_agent.into());
+        self
+    }
+
+    /// Sets whether the providers may send compressed responses, which they
+    /// may by default. Some providers misbehave with compression, so it may
+    /// be turned off without rebuilding the client.
+    ///
+    /// The compressed responses are only accepted when the crate is built
+    /// with the `gzip`, `brotli` or `deflate` features.
+    pub fn with_compression(mut self, enabled: bool) -> UrlShortener {
+        self.compression = enabled;
+        self
+    }
+
+    /// Sends the requests of every provider to the given base URL instead of
+    /// the provider endpoint, keeping their path and query: with the base
+    /// URL `http://localhost:9000/mock`, a request to
+    /// `https://api-ssl.bitly.com/v4/shorten` is sent to
+    /// `http://localhost:9000/mock/v4/shorten`. It is meant for the staging
+    /// environments and the mocks of the provider APIs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_base_url("http://localhost:9000")
+    ///     .on_request(|_, req| assert!(req.url.starts_with("http://localhost:9000/")));
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let _short_url = us.generate("https://rust-lang.org", &provider);
+    /// ```
+    pub fn with_base_url<S: Into<String>>(mut self, base_url: S) -> UrlShortener {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets whether an identifier is generated for the calls which are not
+    /// given one with `ShortenOptions::correlation_id`. The identifier is sent
+    /// in the `X-Correlation-ID` header to the providers which tolerate it,
+    /// is seen by the `on_request` hook and is returned in `ShortenedUrl`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new()
+    ///     .unwrap()
+    ///     .with_correlation_ids(true)
+    ///     .on_request(|provider, req| {
+    ///         let id = req.headers.as_ref().and_then(|h| h.get("X-Correlation-ID"));
+    ///         println!("{}: {:?}", provider, id);
+    ///     });
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let options = ShortenOptions::default();
+    /// if let Ok(shortened) = us.generate_raw("https://rust-lang.org", &provider, &options) {
+    ///     println!("{:?}: {}", shortened.correlation_id, shortened.short_url);
+    /// }
+    /// ```
+    pub fn with_correlation_ids(mut self, enabled: bool) -> UrlShortener {
+        self.correlation_ids = enabled;
+        self
+    }
+
+    /// Sets whether the URL to shorten is checked with a `HEAD` request
+    /// before it is sent to the provider, so that no short URL is created
+    /// for a missing or failing page. The check fails when the page responds
+    /// with `404 Not Found`, `410 Gone` or a server error other than
+    /// `501 Not Implemented`, or when it can't be reached at all.
+    ///
+    /// The URL is checked once per call, before any provider is tried, so
+    /// the multi-provider strategies fail fast with
+    /// `ProviderError::DestinationUnreachable`. The URLs which are not http(s)
+    /// ones are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::{Provider, ProviderError}};
+    ///
+    /// let us = UrlShortener::new().unwrap().with_destination_check(true);
+    /// match us.generate("https://rust-lang.org/no-such-page", &Provider::IsGd) {
+    ///     Err(ProviderError::DestinationUnreachable) => println!("dead link"),
+    ///     res => println!("{:?}", res),
+    /// }
+    /// ```
+    pub fn with_destination_check(mut self, enabled: bool) -> UrlShortener {
+        self.destination_check = enabled;
+        self
+    }
+
+    /// Sets the callback which signs every request before it is sent, for
+    /// example adding a header with the HMAC of the method, the URL and the
+    /// body of the request. The callback is given the provider the request is
+    /// sent to, so it may sign the requests to some providers only.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap().with_signer(|provider, req| {
+    ///     if let Provider::Kutt { .. } = *provider {
+    ///         let payload = format!(
+    ///             "{} {} {}",
+    ///             req.method.as_str(),
+    ///             req.url,
+    ///             req.body.as_deref().unwrap_or("")
+    ///         );
+    ///         // Compute the signature of the payload with your key here.
+    ///         let signature = payload.len().to_string();
+    ///         req.headers
+    ///             .get_or_insert_with(Default::default)
+    ///             .insert("X-Signature", signature.parse().unwrap());
+    ///     }
+    /// });
+    /// ```
+    pub fn with_signer<F>(mut self, signer: F) -> UrlShortener
+    where
+        F: Fn(&providers::Provider, &mut Request) + Send + Sync + 'static,
+    {
+        self.signer = Some(Arc::new(signer));
+        self
     }
 
     /// Try to generate a short URL from each provider, iterating over each
@@ -43,7 +815,7 @@ impl UrlShortener {
     ///
     /// let us = UrlShortener::new().unwrap();
     /// let providers = [
-    ///     Provider::GooGl { api_key: "MY_API_KEY".to_owned() },
+    ///     Provider::BitLy { token: "MY_TOKEN".to_owned() },
     ///     Provider::IsGd,
     /// ];
     /// let long_url = "https://rust-lang.org";
@@ -52,8 +824,9 @@ impl UrlShortener {
     ///
     /// # Errors
     ///
-    /// Returns an `Error<ProviderError>` if there is an error generating a
-    /// short URL from all providers.
+    /// Returns the error of the last attempt if all the providers fail,
+    /// `ProviderError::RateLimited` if none was attempted because they are
+    /// cooling down, or `ProviderError::Connection` if there is no provider.
     ///
     /// # Notes
     ///
@@ -65,7 +838,7 @@ impl UrlShortener {
     ///
     /// let us = UrlShortener::new().unwrap();
     /// let providers = [
-    ///     Provider::GooGl { api_key: "MY_API_KEY".to_owned() },
+    ///     Provider::BitLy { token: "MY_TOKEN".to_owned() },
     ///     Provider::IsGd,
     /// ];
     /// let long_url = "https://rust-lang.org";
@@ -85,15 +858,34 @@ impl UrlShortener {
         use_providers: Option<&[providers::Provider]>,
     ) -> Result<String, ProviderError> {
         let providers = use_providers.unwrap_or(providers::PROVIDERS);
+        let deadline = self.deadline();
+        self.check_destination(url)?;
+        let mut last_error = None;
+        let mut cooling_down = false;
         for provider in providers {
-            let res = self.generate(url, provider);
+            if time_left(deadline) == Some(Duration::ZERO) {
+                break;
+            }
+            if self.is_cooling_down(provider) {
+                cooling_down = true;
+                continue;
+            }
+            let res = self.generate_tiered(url, provider, &ShortenOptions::default(), deadline);
 
-            if res.is_ok() {
-                return res;
+            match res {
+                Ok(_) | Err(ProviderError::DestinationUnreachable) => return res,
+                Err(e) => {
+                    self.cool_down_if_overloaded(provider, e);
+                    last_error = Some(e);
+                }
             }
         }
 
-        Err(ProviderError::Connection)
+        Err(match last_error {
+            Some(e) => e,
+            None if cooling_down => ProviderError::RateLimited,
+            None => ProviderError::Connection,
+        })
     }
 
     /// Attempts to get a short URL using the specified provider.
@@ -112,32 +904,1501 @@ impl UrlShortener {
     /// use urlshortener::{providers::Provider, client::UrlShortener};
     ///
     /// let us = UrlShortener::new().unwrap();
-    /// let api_key = "MY_API_KEY".to_owned();
+    /// let token = "MY_TOKEN".to_owned();
     /// let long_url = "http://rust-lang.org";
-    /// let _short_url = us.generate(long_url, &Provider::GooGl { api_key: api_key });
+    /// let _short_url = us.generate(long_url, &Provider::BitLy { token: token });
     /// ```
     pub fn generate<S: AsRef<str>>(
         &self,
         url: S,
         provider: &providers::Provider,
     ) -> Result<String, ProviderError> {
-        let req = request(url.as_ref(), provider);
+        self.generate_with_options(url, provider, &ShortenOptions::default())
+    }
 
-        if let Ok(response) = req.execute(&self.client) {
-            response
-                .text()
-                .map_err(|_| ProviderError::Connection)
-                .and_then(|t| parse(&t, provider))
-        } else {
-            Err(ProviderError::Connection)
+    /// Attempts to get a short URL using the specified provider and the
+    /// additional link options. The options which the provider does not
+    /// support are handled according to the [`ShortenOptions::mode`]: with the
+    /// default `OptionsMode::Strict` the call fails with
+    /// `ProviderError::UnsupportedOption`, with `OptionsMode::Lenient` they are
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let options = ShortenOptions::new().title("Rust").tag("rust");
+    /// let _short_url = us.generate_with_options("https://rust-lang.org", &provider, &options);
+    /// ```
+    pub fn generate_with_options<S: AsRef<str>>(
+        &self,
+        url: S,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Result<String, ProviderError> {
+        self.generate_raw(url, provider, options)
+            .map(|shortened| shortened.short_url)
+    }
+
+    /// Attempts to get a short URL using the specified provider with the
+    /// credential obtained from the credential provider, instead of the one
+    /// stored in the `Provider` variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use urlshortener::{
+    ///     client::UrlShortener,
+    ///     credentials::{RefreshingToken, Secret},
+    ///     options::ShortenOptions,
+    ///     providers::Provider,
+    /// };
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::SnipLy { token: String::new(), cta: None, campaign: None };
+    /// let credentials = RefreshingToken::new(|_: &Provider| {
+    ///     // Exchange the refresh token for a new access token here.
+    ///     Ok((Secret::new("MY_ACCESS_TOKEN"), Duration::from_secs(3600)))
+    /// });
+    /// let options = ShortenOptions::default();
+    /// let _short_url =
+    ///     us.generate_with_credentials("https://rust-lang.org", &provider, &options, &credentials);
+    /// ```
+    pub fn generate_with_credentials<S: AsRef<str>>(
+        &self,
+        url: S,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+        credentials: &dyn CredentialProvider,
+    ) -> Result<String, ProviderError> {
+        let req = request_with_credentials(
+            url.as_ref(),
+            provider,
+            &request_options(provider, options),
+            credentials,
+        )?;
+        self.send(provider, req, |t| self.parse(t, provider, options))
+    }
+
+    /// Attempts to get a short URL from the providers of the `PROVIDERS` list,
+    /// in the order of `ranked_providers`, until one succeeds. The collected
+    /// statistics are saved to the storage afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt if all the providers fail.
+    pub fn generate_adaptive<S: AsRef<str>>(
+        &self,
+        url: S,
+        options: &ShortenOptions,
+    ) -> Result<String, ProviderError> {
+        let mut res = Err(ProviderError::Connection);
+        let deadline = self.deadline();
+        self.check_destination(url.as_ref())?;
+        for provider in self.ranked_providers() {
+            if time_left(deadline) == Some(Duration::ZERO) {
+                break;
+            }
+            if self.is_cooling_down(&provider) {
+                continue;
+            }
+            res = self.generate_tiered(url.as_ref(), &provider, options, deadline);
+            match res {
+                Ok(_) | Err(ProviderError::DestinationUnreachable) => break,
+                Err(e) => self.cool_down_if_overloaded(&provider, e),
+            }
         }
+
+        // The statistics are an optimization, failing to save them must not
+        // fail the shortening.
+        let _ = self.save_stats();
+        res
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::client;
-    use crate::providers;
+    /// Attempts to get a short URL using the next credentials of the rotation.
+    /// When the provider rejects the credentials, reports them as rate
+    /// limited or is unavailable, the following credentials are tried in
+    /// turn. The credentials which are cooling down are skipped (see
+    /// `with_cooldown`).
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt if all the credentials fail,
+    /// `ProviderError::RateLimited` if they are all cooling down, or
+    /// `ProviderError::Connection` if the rotation is empty.
+    pub fn generate_rotating<S: AsRef<str>>(
+        &self,
+        url: S,
+        rotation: &ProviderRotation,
+        options: &ShortenOptions,
+    ) -> Result<String, ProviderError> {
+        let mut res = Err(ProviderError::Connection);
+        let deadline = self.deadline();
+        self.check_destination(url.as_ref())?;
+
+        for provider in rotation.next_round() {
+            let timeout = time_left(deadline);
+            if timeout == Some(Duration::ZERO) {
+                break;
+            }
+            if self.is_cooling_down(provider) {
+                res = Err(ProviderError::RateLimited);
+                continue;
+            }
+            res = self
+                .shorten(url.as_ref(), provider, options, timeout)
+                .map(|shortened| shortened.short_url);
+            match res {
+                Err(e) if is_overloaded(e) => self.cool_down_if_overloaded(provider, e),
+                Err(e) if e.kind() == ProviderError::Unauthorized => continue,
+                _ => break,
+            }
+        }
+        res
+    }
+
+    /// Attempts to get short URLs for all the URLs using the specified
+    /// provider and the additional link options, one by one, passing each
+    /// outcome to the callback as soon as it completes. Unlike
+    /// `generate_batch`, the outcomes are not kept, so that huge jobs may be
+    /// streamed to the downstream processors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::io;
+    /// use urlshortener::{batch, client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let urls = ["https://google.com", "https://rust-lang.org"];
+    /// us.generate_each(&urls, &Provider::IsGd, &ShortenOptions::default(), |record| {
+    ///     batch::write_jsonl(io::stdout(), &record).unwrap();
+    /// });
+    /// ```
+    pub fn generate_each<S, F>(
+        &self,
+        urls: &[S],
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+        mut on_record: F,
+    ) where
+        S: AsRef<str>,
+        F: FnMut(BatchRecord),
+    {
+        for url in urls {
+            let result = self.generate_with_options(url.as_ref(), provider, options);
+            on_record(BatchRecord::new(url.as_ref(), provider.to_name(), result));
+        }
+    }
+
+    /// Attempts to get short URLs for all the URLs like `generate_each` does,
+    /// saving the progress to the checkpoint. The URLs which the checkpoint
+    /// holds as done are not shortened again: their saved records are passed
+    /// to the callback instead.
+    ///
+    /// Fails only if the checkpoint can't be saved, in which case the job
+    /// stops so that no quota is spent on the progress which would be lost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{
+    ///     batch::{self, Checkpoint},
+    ///     client::UrlShortener,
+    ///     options::ShortenOptions,
+    ///     providers::Provider,
+    /// };
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let urls = batch::from_csv(std::fs::File::open("urls.csv").unwrap()).unwrap();
+    /// let mut checkpoint = Checkpoint::open("urls.checkpoint").unwrap();
+    /// let mut records = Vec::new();
+    /// us.generate_resumable(&urls, &Provider::IsGd, &ShortenOptions::default(), &mut checkpoint, |r| {
+    ///     records.push(r)
+    /// })
+    /// .unwrap();
+    /// batch::to_csv(std::fs::File::create("short.csv").unwrap(), &records).unwrap();
+    /// ```
+    pub fn generate_resumable<S, F>(
+        &self,
+        urls: &[S],
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+        checkpoint: &mut Checkpoint,
+        mut on_record: F,
+    ) -> std::io::Result<()>
+    where
+        S: AsRef<str>,
+        F: FnMut(BatchRecord),
+    {
+        for url in urls {
+            if let Some(record) = checkpoint.get(url.as_ref()) {
+                on_record(record.clone());
+                continue;
+            }
+
+            let result = self.generate_with_options(url.as_ref(), provider, options);
+            let record = BatchRecord::new(url.as_ref(), provider.to_name(), result);
+            checkpoint.record(&record)?;
+            on_record(record);
+        }
+        Ok(())
+    }
+
+    /// Attempts to get short URLs for all the URLs, spreading them across the
+    /// providers so that none of them is sent more requests than its quota
+    /// (see `with_quota` and `Provider::default_quota`) allows. Each URL is sent to the
+    /// provider which is able to accept a request the soonest, the least
+    /// used one among those which are able to right away; the call sleeps
+    /// when all the providers have exhausted their quotas.
+    ///
+    /// A URL is sent to another provider when the one picked reports being
+    /// rate limited or unavailable, the provider is not picked again until
+    /// it has cooled down (see `with_cooldown`).
+    ///
+    /// Each outcome is passed to the callback as soon as it completes. The
+    /// URLs fail with `ProviderError::Connection` if no provider is given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let urls = ["https://google.com", "https://rust-lang.org"];
+    /// let providers = [Provider::IsGd, Provider::VGd, Provider::BamBz];
+    /// us.generate_scheduled(&urls, &providers, &ShortenOptions::default(), |record| {
+    ///     println!("{:?}", record);
+    /// });
+    /// ```
+    pub fn generate_scheduled<S, F>(
+        &self,
+        urls: &[S],
+        providers: &[providers::Provider],
+        options: &ShortenOptions,
+        mut on_record: F,
+    ) where
+        S: AsRef<str>,
+        F: FnMut(BatchRecord),
+    {
+        let mut budgets = providers
+            .iter()
+            .map(|provider| Budget::new(provider, self.quota(provider)))
+            .filter(|budget| budget.quota.map(|q| q.requests) != Some(0))
+            .collect::<Vec<_>>();
+
+        for url in urls {
+            let mut record = BatchRecord::new(url.as_ref(), "", Err(ProviderError::Connection));
+
+            let deadline = self.deadline();
+            if let Err(e) = self.check_destination(url.as_ref()) {
+                record.result = Err(e);
+                on_record(record);
+                continue;
+            }
+            for _ in 0..budgets.len() {
+                let now = Instant::now();
+                let (at, budget) = match budgets
+                    .iter_mut()
+                    .map(|budget| {
+                        let cooled_down = self.cooldown_until(budget.provider).unwrap_or(now);
+                        (budget.available_at(now).max(cooled_down), budget)
+                    })
+                    .min_by_key(|(at, budget)| (*at, budget.used))
+                {
+                    Some(budget) => budget,
+                    None => break,
+                };
+                if deadline.map(|deadline| at >= deadline) == Some(true) {
+                    if record.provider.is_empty() {
+                        record.result = Err(ProviderError::RateLimited);
+                    }
+                    break;
+                }
+                std::thread::sleep(at.saturating_duration_since(Instant::now()));
+
+                budget.spend(Instant::now());
+                let result = self
+                    .shorten(url.as_ref(), budget.provider, options, time_left(deadline))
+                    .map(|shortened| shortened.short_url);
+                record = BatchRecord::new(url.as_ref(), budget.provider.to_name(), result);
+                match record.result {
+                    Err(e) if is_overloaded(e) => self.cool_down_if_overloaded(budget.provider, e),
+                    _ => break,
+                }
+            }
+            on_record(record);
+        }
+    }
+
+    /// Attempts to get a short URL using the specified provider and the
+    /// additional link options, like `generate_with_options` does, along with
+    /// the details of the provider response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let options = ShortenOptions::default();
+    /// if let Ok(shortened) = us.generate_raw("https://rust-lang.org", &provider, &options) {
+    ///     if let Some(remaining) = shortened.rate_limit.and_then(|r| r.remaining) {
+    ///         println!("{} requests left", remaining);
+    ///     }
+    /// }
+    /// ```
+    pub fn generate_raw<S: AsRef<str>>(
+        &self,
+        url: S,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Result<ShortenedUrl, ProviderError> {
+        self.check_destination(url.as_ref())?;
+        self.shorten(url.as_ref(), provider, options, None)
+    }
+
+    /// Shortens the URL like `generate_raw` does, waiting for the provider
+    /// for the given time instead of the client timeout.
+    fn shorten(
+        &self,
+        url: &str,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+        timeout: Option<Duration>,
+    ) -> Result<ShortenedUrl, ProviderError> {
+        let started = Instant::now();
+        let correlation_id = self.correlation_id(options);
+        let res = if provider.is_local() {
+            providers::shorten_locally(url, provider, options).map(|short_url| {
+                let received = Received {
+                    rate_limit: None,
+                    first_byte: started.elapsed(),
+                };
+                (short_url, received)
+            })
+        } else {
+            self.shorten_request(url, provider, options, correlation_id.as_deref())
+                .and_then(|req| {
+                    self.send_raw(provider, req, timeout, |t| self.parse(t, provider, options))
+                })
+        };
+
+        if let Some(ref adaptive) = self.adaptive {
+            if let Ok(mut stats) = adaptive.stats.lock() {
+                stats
+                    .entry(provider.to_name().to_owned())
+                    .or_default()
+                    .record(res.is_ok(), started.elapsed());
+            }
+        }
+
+        let (short_url, received) = res?;
+
+        Ok(ShortenedUrl {
+            short_url,
+            rate_limit: received.rate_limit,
+            correlation_id,
+            timing: Timing {
+                first_byte: received.first_byte,
+                total: started.elapsed(),
+            },
+        })
+    }
+
+    /// Attempts to get short URLs for all the URLs using the specified
+    /// provider and the additional link options, returning the results in the
+    /// order of the URLs.
+    ///
+    /// The provider bulk API is used when there is one (see
+    /// `Provider::max_bulk_size`), sending one request per chunk of URLs
+    /// instead of one per URL. Otherwise the URLs are shortened one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::ShortIo {
+    ///     api_key: "MY_API_KEY".to_owned(),
+    ///     domain: "example.com".to_owned(),
+    /// };
+    /// let urls = ["https://google.com", "https://rust-lang.org"];
+    /// for res in us.generate_batch(&urls, &provider, &ShortenOptions::default()) {
+    ///     println!("{:?}", res);
+    /// }
+    /// ```
+    pub fn generate_batch<S: AsRef<str>>(
+        &self,
+        urls: &[S],
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Vec<Result<String, ProviderError>> {
+        let urls = urls.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+
+        let chunk_size = match provider.max_bulk_size() {
+            Some(size) if options.alias.is_none() => size,
+            _ => {
+                return urls
+                    .iter()
+                    .map(|url| self.generate_with_options(url, provider, options))
+                    .collect()
+            }
+        };
+
+        let mut results = Vec::with_capacity(urls.len());
+        for chunk in urls.chunks(chunk_size) {
+            let res = bulk_request(chunk, provider, options)
+                .and_then(|req| self.send(provider, req, |t| parse_bulk(t, provider)));
+
+            match res {
+                Ok(links) if links.len() == chunk.len() => results.extend(links),
+                Ok(_) => results.extend(chunk.iter().map(|_| Err(ProviderError::Deserialize))),
+                Err(e) => results.extend(chunk.iter().map(|_| Err(e))),
+            }
+        }
+        results
+    }
+
+    /// Builds the request which `generate_with_options` would send, without
+    /// sending it, so that it may be inspected or sent with other tools. The
+    /// default headers, the correlation identifier and the signature are
+    /// added to it, like to the requests which are sent; the hooks are not
+    /// called.
+    ///
+    /// The `Debug` output of the request hides the credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use urlshortener::{client::UrlShortener, options::ShortenOptions, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let req = us
+    ///     .generate_dry_run("https://rust-lang.org", &provider, &ShortenOptions::default())
+    ///     .unwrap();
+    /// assert_eq!(req.url, "https://api-ssl.bitly.com/v4/bitlinks");
+    /// assert!(!format!("{:?}", req).contains("MY_TOKEN"));
+    /// ```
+    pub fn generate_dry_run<S: AsRef<str>>(
+        &self,
+        url: S,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Result<Request, ProviderError> {
+        let correlation_id = self.correlation_id(options);
+        self.shorten_request(url.as_ref(), provider, options, correlation_id.as_deref())
+            .map(|req| self.prepare(provider, req))
+    }
+
+    /// Builds the requests which `generate_batch` would send, without sending
+    /// them (see `generate_dry_run`). When the provider has a bulk API, there
+    /// is one request per chunk of URLs instead of one per URL.
+    pub fn batch_dry_run<S: AsRef<str>>(
+        &self,
+        urls: &[S],
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Vec<Result<Request, ProviderError>> {
+        let urls = urls.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+
+        match provider.max_bulk_size() {
+            Some(size) if options.alias.is_none() => urls
+                .chunks(size)
+                .map(|chunk| {
+                    bulk_request(chunk, provider, options).map(|req| self.prepare(provider, req))
+                })
+                .collect(),
+            _ => urls
+                .iter()
+                .map(|url| self.generate_dry_run(url, provider, options))
+                .collect(),
+        }
+    }
+
+    /// Attempts to resolve a short URL into the original one using the API of
+    /// the specified provider.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let _long_url = us.expand_via_provider("https://bit.ly/2Ewn0ZT", &provider);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::Unsupported` if the provider does not offer an
+    /// API for expanding short URLs.
+    pub fn expand_via_provider<S: AsRef<str>>(
+        &self,
+        short_url: S,
+        provider: &providers::Provider,
+    ) -> Result<String, ProviderError> {
+        if let providers::Provider::Local { .. } = *provider {
+            return providers::expand_locally(short_url.as_ref(), provider);
+        }
+        let req = expand_request(short_url.as_ref(), provider)?;
+        self.send(provider, req, |t| parse_expand(t, provider))
+    }
+
+    /// Follows the redirects from the short URL, returning every hop up to
+    /// `max_hops` of them. The chain ends at the first URL which does not
+    /// redirect, or at a URL which redirects to one visited before.
+    ///
+    /// Besides the HTTP redirects, the `Refresh` header and the
+    /// `<meta http-equiv="refresh">` tags of the HTML pages are followed, as
+    /// the preview and interstitial pages of some shorteners redirect this
+    /// way (see `expand::meta_refresh`).
+    ///
+    /// The redirects are followed one by one with the client set with
+    /// `with_redirect_client`, sending the user agent and the default headers
+    /// of this client. A `UrlShortener` created with `with_client` has none
+    /// unless it is set: a client with the default settings is built for
+    /// each call then, so the proxy and TLS settings of the given client do
+    /// not apply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::client::UrlShortener;
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let chain = us.expand_chain("https://bit.ly/2Ewn0ZT", 10).unwrap();
+    /// if chain.is_multi_shortened() {
+    ///     println!("{:?} hides its destination", chain.hops.first());
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::InvalidUrl` if the short URL or a redirect
+    /// location is not a valid URL, and `ProviderError::Connection` if a
+    /// request fails.
+    pub fn expand_chain<S: AsRef<str>>(
+        &self,
+        short_url: S,
+        max_hops: usize,
+    ) -> Result<RedirectChain, ProviderError> {
+        let default_client;
+        let client = match self.redirect_client {
+            Some(ref client) => client,
+            None => {
+                default_client = ClientBuilder::new()
+                    .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+                    .redirect(redirect::Policy::none())
+                    .build()
+                    .map_err(|_| ProviderError::Connection)?;
+                &default_client
+            }
+        };
+
+        let mut url = Url::parse(short_url.as_ref()).map_err(|_| ProviderError::InvalidUrl)?;
+        let mut visited = HashSet::new();
+        let mut hops = Vec::new();
+
+        loop {
+            if hops.len() == max_hops {
+                return Ok(RedirectChain {
+                    hops,
+                    end: ChainEnd::TooLong,
+                });
+            }
+            visited.insert(url.clone());
+
+            let mut builder = client
+                .get(url.as_str())
+                .headers(self.default_headers.clone());
+            if let Some(ref user_agent) = self.user_agent {
+                builder = builder.header(USER_AGENT, user_agent.as_str());
+            }
+            let response = builder.send().map_err(|_| ProviderError::Connection)?;
+            hops.push(Hop {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+                content_type: response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|c| c.to_str().ok())
+                    .map(String::from),
+            });
+
+            let next = match redirect_target(response) {
+                Some(location) => url.join(&location).map_err(|_| ProviderError::InvalidUrl)?,
+                None => {
+                    return Ok(RedirectChain {
+                        hops,
+                        end: ChainEnd::Destination,
+                    })
+                }
+            };
+            if visited.contains(&next) {
+                return Ok(RedirectChain {
+                    hops,
+                    end: ChainEnd::Loop,
+                });
+            }
+            url = next;
+        }
+    }
+
+    /// Follows the redirects from the short URL to its destination, like
+    /// `expand_chain` does with at most 10 hops, returning the destination
+    /// along with how it was reached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, expand::ChainEnd};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let expanded = us.expand("https://bit.ly/2Ewn0ZT").unwrap();
+    /// if expanded.end == ChainEnd::Destination && expanded.status == 200 {
+    ///     println!("{} ({:?})", expanded.final_url, expanded.content_type);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `expand_chain`.
+    pub fn expand<S: AsRef<str>>(&self, short_url: S) -> Result<ExpandedUrl, ProviderError> {
+        const MAX_HOPS: usize = 10;
+
+        let chain = self.expand_chain(short_url, MAX_HOPS)?;
+        ExpandedUrl::from_chain(chain, SystemTime::now()).ok_or(ProviderError::Connection)
+    }
+
+    /// Attempts to retrieve the click statistics of a short URL over the given
+    /// period using the API of the specified provider.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{
+    ///     client::UrlShortener,
+    ///     providers::Provider,
+    ///     stats::{StatsPeriod, StatsUnit},
+    /// };
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// let last_week = StatsPeriod { unit: StatsUnit::Day, units: Some(7) };
+    /// let _stats = us.stats("https://bit.ly/2Ewn0ZT", &provider, &last_week);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::Unsupported` if the provider does not offer an
+    /// API for link statistics.
+    pub fn stats<S: AsRef<str>>(
+        &self,
+        short_url: S,
+        provider: &providers::Provider,
+        period: &StatsPeriod,
+    ) -> Result<LinkStats, ProviderError> {
+        let req = stats_request(short_url.as_ref(), provider, period)?;
+        self.send(provider, req, |t| parse_stats(t, provider))
+    }
+
+    /// Attempts to list the links stored on the provider side, skipping the
+    /// first `skip` links and returning at most `limit` of them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::Kutt { api_key: "MY_API_KEY".to_owned(), host: None };
+    /// if let Ok(page) = us.list_links(&provider, 0, 10) {
+    ///     for link in page.links {
+    ///         println!("{} -> {}", link.short_url, link.target);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::Unsupported` if the provider does not offer an
+    /// API for listing links.
+    pub fn list_links(
+        &self,
+        provider: &providers::Provider,
+        skip: u64,
+        limit: u64,
+    ) -> Result<LinkPage, ProviderError> {
+        let req = list_links_request(provider, skip, limit)?;
+        self.send(provider, req, |t| parse_list_links(t, provider))
+    }
+
+    /// Attempts to retrieve all the links stored on the provider side, page
+    /// by page, passing each link to the callback as soon as its page is
+    /// received, so that a whole link portfolio may be backed up or migrated
+    /// without keeping it in memory. Returns the number of the links.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::Kutt { api_key: "MY_API_KEY".to_owned(), host: None };
+    /// let exported = us.export_links(&provider, |link| {
+    ///     println!("{},{}", link.short_url, link.target);
+    /// });
+    /// println!("{:?} links exported", exported);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::Unsupported` if the provider does not offer an
+    /// API for listing links, or the error of the page which failed. The
+    /// links of the pages received before are passed to the callback anyway.
+    pub fn export_links<F: FnMut(Link)>(
+        &self,
+        provider: &providers::Provider,
+        mut on_link: F,
+    ) -> Result<u64, ProviderError> {
+        const PAGE_SIZE: u64 = 50;
+
+        let mut exported = 0;
+        loop {
+            let page = self.list_links(provider, exported, PAGE_SIZE)?;
+            let received = page.links.len() as u64;
+            exported += received;
+            page.links.into_iter().for_each(&mut on_link);
+
+            let done = match page.total {
+                Some(total) => exported >= total,
+                None => received < PAGE_SIZE,
+            };
+            if done || received == 0 {
+                return Ok(exported);
+            }
+        }
+    }
+
+    /// Attempts to retrieve the links created or changed after the given
+    /// time, for example the time of the previous synchronisation, so that
+    /// the link lists may be polled without downloading them whole.
+    ///
+    /// The providers list the newest links first, so the listing stops at
+    /// the first link created before the given time. The links created
+    /// before it but changed after it are only reported when they are on the
+    /// pages read. The links whose times are not reported are returned
+    /// anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::{Duration, SystemTime};
+    /// use urlshortener::{client::UrlShortener, providers::Provider};
+    ///
+    /// let us = UrlShortener::new().unwrap();
+    /// let provider = Provider::Kutt { api_key: "MY_API_KEY".to_owned(), host: None };
+    /// let checkpoint = SystemTime::now() - Duration::from_secs(3600);
+    /// if let Ok(links) = us.sync_links(&provider, checkpoint) {
+    ///     println!("{} links changed in the last hour", links.len());
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::Unsupported` if the provider does not offer an
+    /// API for listing links, or the error of the page which failed.
+    pub fn sync_links(
+        &self,
+        provider: &providers::Provider,
+        since: SystemTime,
+    ) -> Result<Vec<Link>, ProviderError> {
+        const PAGE_SIZE: u64 = 50;
+
+        let mut links = Vec::new();
+        let mut skip = 0;
+        loop {
+            let page = self.list_links(provider, skip, PAGE_SIZE)?;
+            let received = page.links.len() as u64;
+            skip += received;
+
+            let mut older = false;
+            for link in page.links {
+                let created = link.created_at.as_deref().and_then(parse_timestamp);
+                older |= created.map(|c| c <= since) == Some(true);
+                if link.modified_at().map(|m| m > since) != Some(false) {
+                    links.push(link);
+                }
+            }
+
+            let done = match page.total {
+                Some(total) => skip >= total,
+                None => received < PAGE_SIZE,
+            };
+            if older || done || received == 0 {
+                return Ok(links);
+            }
+        }
+    }
+
+    /// Attempts to delete the link with the given provider identifier (see
+    /// `Link::id`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProviderError::Unsupported` if the provider does not offer an
+    /// API for deleting links.
+    pub fn delete<S: AsRef<str>>(
+        &self,
+        id: S,
+        provider: &providers::Provider,
+    ) -> Result<(), ProviderError> {
+        let req = delete_request(id.as_ref(), provider)?;
+        self.send(provider, req, |t| parse_delete(t, provider))
+    }
+
+    /// Parses the short URL from the response text and checks it with the
+    /// validators of the provider.
+    fn parse(
+        &self,
+        res: &str,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Result<String, ProviderError> {
+        let mut short_url = parse_with_options(res, provider, options)?;
+        if options.preview && provider.previews_with_hyphen() {
+            short_url.push('-');
+        }
+        let is_valid = self
+            .validators
+            .iter()
+            .filter(|(name, _)| name == provider.to_name())
+            .all(|(_, validator)| validator(&short_url));
+
+        if is_valid {
+            Ok(short_url)
+        } else {
+            Err(ProviderError::Deserialize)
+        }
+    }
+
+    /// Signs and executes the request to the provider, and parses the
+    /// response text.
+    fn send<T, F>(
+        &self,
+        provider: &providers::Provider,
+        req: Request,
+        parse: F,
+    ) -> Result<T, ProviderError>
+    where
+        F: FnOnce(&str) -> Result<T, ProviderError>,
+    {
+        self.send_raw(provider, req, None, parse)
+            .map(|(res, _)| res)
+    }
+
+    /// Shortens the URL for a strategy trying several providers, waiting for
+    /// the provider according to its timeout tier (see
+    /// `Provider::timeout_tier`), so that less time is lost on a fast
+    /// provider which is down. The tiers only apply when the client timeout
+    /// is known, that is unless the client was given with `with_client`.
+    fn generate_tiered(
+        &self,
+        url: &str,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+        deadline: Option<Instant>,
+    ) -> Result<String, ProviderError> {
+        let tiered = self.timeout.map(|t| provider.timeout_tier().scale(t));
+        let timeout = match (tiered, time_left(deadline)) {
+            (Some(tiered), Some(left)) => Some(tiered.min(left)),
+            (tiered, left) => tiered.or(left),
+        };
+        self.shorten(url, provider, options, timeout)
+            .map(|shortened| shortened.short_url)
+    }
+
+    /// Returns the identifier of the call: the one given in the options, or a
+    /// new one if the client generates them.
+    fn correlation_id(&self, options: &ShortenOptions) -> Option<String> {
+        options
+            .correlation_id
+            .clone()
+            .or_else(|| self.correlation_ids.then(new_correlation_id))
+    }
+
+    /// Builds the request shortening the URL, with the correlation identifier
+    /// header for the providers which tolerate it.
+    fn shorten_request(
+        &self,
+        url: &str,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+        correlation_id: Option<&str>,
+    ) -> Result<Request, ProviderError> {
+        let mut req = request_with_options(url, provider, &request_options(provider, options))?;
+        let id = correlation_id.map(HeaderValue::from_str);
+        if let (Some(Ok(id)), true) = (id, provider.tolerates_extra_headers()) {
+            req.headers
+                .get_or_insert_with(HeaderMap::new)
+                .insert("X-Correlation-ID", id);
+        }
+        Ok(req)
+    }
+
+    /// Checks that the URL to shorten leads to a live page, if the check is
+    /// enabled. The URLs which are not http(s) ones, like the `magnet:` URIs,
+    /// are not checked.
+    fn check_destination(&self, url: &str) -> Result<(), ProviderError> {
+        if !self.destination_check {
+            return Ok(());
+        }
+        match Url::parse(url) {
+            Ok(ref url) if url.scheme() == "http" || url.scheme() == "https" => {}
+            _ => return Ok(()),
+        }
+
+        let mut builder = self.client.head(url);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = builder
+            .send()
+            .map_err(|_| ProviderError::DestinationUnreachable)?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND
+            || status == StatusCode::GONE
+            || (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+        {
+            return Err(ProviderError::DestinationUnreachable);
+        }
+        Ok(())
+    }
+
+    /// Adds the default headers and the signature to the request, as it is
+    /// about to be sent.
+    fn prepare(&self, provider: &providers::Provider, mut req: Request) -> Request {
+        match self.user_agents.get(provider.to_name()) {
+            Some(user_agent) => req.user_agent = Some(UserAgent(user_agent.clone())),
+            None if req.user_agent.is_none() => {
+                req.user_agent = self.user_agent.clone().map(UserAgent);
+            }
+            None => {}
+        }
+        if let Some(ref base_url) = self.base_url {
+            req.url = rebase(&req.url, base_url);
+        }
+        if let Some(extra) = self.provider_headers.get(provider.to_name()) {
+            let headers = req.headers.get_or_insert_with(HeaderMap::new);
+            for (name, value) in extra {
+                headers.insert(name, value.clone());
+            }
+        }
+        if !self.default_headers.is_empty() {
+            let headers = req.headers.get_or_insert_with(HeaderMap::new);
+            for (name, value) in &self.default_headers {
+                if !headers.contains_key(name) {
+                    headers.insert(name, value.clone());
+                }
+            }
+        }
+        if !self.compression {
+            req.headers
+                .get_or_insert_with(HeaderMap::new)
+                .insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+        }
+        if let Some(ref signer) = self.signer {
+            signer(provider, &mut req);
+        }
+        req
+    }
+
+    /// Returns when the provider stops cooling down, if it is cooling down.
+    fn cooldown_until(&self, provider: &providers::Provider) -> Option<Instant> {
+        let mut cooldowns = self.cooldowns.lock().ok()?;
+        match cooldowns.get(provider) {
+            Some(&until) if until > Instant::now() => Some(until),
+            Some(_) => {
+                cooldowns.remove(provider);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Makes the provider cool down for the given duration.
+    fn cool_down(&self, provider: &providers::Provider, duration: Duration) {
+        let now = Instant::now();
+        let until = match now.checked_add(duration) {
+            Some(until) => until,
+            None => match now.checked_add(self.cooldown) {
+                Some(until) => until,
+                None => return,
+            },
+        };
+        if let Ok(mut cooldowns) = self.cooldowns.lock() {
+            cooldowns.insert(provider.clone(), until);
+        }
+    }
+
+    /// Returns when the time budget of a call starting now is spent, if
+    /// there is one. A budget too large to be represented is no budget.
+    fn deadline(&self) -> Option<Instant> {
+        self.deadline.and_then(|d| Instant::now().checked_add(d))
+    }
+
+    /// Makes the provider cool down for the default duration if the error
+    /// tells it is rate limited or unavailable, unless it is cooling down
+    /// already, for example for the duration the provider asked for.
+    fn cool_down_if_overloaded(&self, provider: &providers::Provider, error: ProviderError) {
+        if is_overloaded(error) && !self.is_cooling_down(provider) {
+            self.cool_down(provider, self.cooldown);
+        }
+    }
+
+    /// Returns the number of requests the provider is sent at most within a
+    /// time window: the configured one or the default one.
+    fn quota(&self, provider: &providers::Provider) -> Option<Quota> {
+        self.quotas
+            .get(provider.to_name())
+            .copied()
+            .or_else(|| provider.default_quota())
+    }
+
+    /// Signs and executes the request to the provider, and parses the
+    /// response text, returning it along with the details of the response.
+    /// The failures are reported to the `on_error` hook.
+    fn send_raw<T, F>(
+        &self,
+        provider: &providers::Provider,
+        req: Request,
+        timeout: Option<Duration>,
+        parse: F,
+    ) -> Result<(T, Received), ProviderError>
+    where
+        F: FnOnce(&str) -> Result<T, ProviderError>,
+    {
+        let started = Instant::now();
+        let res = self
+            .execute(provider, req, timeout, started)
+            .and_then(|(text, received)| parse(&text).map(|res| (res, received)));
+
+        if let (Err(e), Some(on_error)) = (&res, &self.hooks.on_error) {
+            on_error(provider.to_name(), *e, started.elapsed());
+        }
+        res
+    }
+
+    /// Signs and executes the request to the provider, returning the response
+    /// text along with the details of the response. The client timeout is
+    /// overriden by `timeout` if given.
+    fn execute(
+        &self,
+        provider: &providers::Provider,
+        req: Request,
+        timeout: Option<Duration>,
+        started: Instant,
+    ) -> Result<(String, Received), ProviderError> {
+        let req = self.prepare(provider, req);
+        if let Some(ref on_request) = self.hooks.on_request {
+            on_request(provider.to_name(), &req);
+        }
+
+        let client = self
+            .transports
+            .iter()
+            .find(|(base_url, _)| req.url.starts_with(base_url.as_str()))
+            .map_or(&self.client, |(_, client)| client);
+
+        let mut builder = req.to_reqwest(client);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = builder.send().map_err(|_| ProviderError::Connection)?;
+        let first_byte = started.elapsed();
+        if let Some(ref on_response) = self.hooks.on_response {
+            on_response(
+                provider.to_name(),
+                response.status().as_u16(),
+                started.elapsed(),
+            );
+        }
+
+        match response.status() {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                let retry_after = retry_after(response.headers());
+                self.cool_down(provider, retry_after.unwrap_or(self.cooldown));
+                return Err(match response.status() {
+                    StatusCode::TOO_MANY_REQUESTS => ProviderError::RateLimited,
+                    _ => ProviderError::Unavailable,
+                });
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(ProviderError::Unauthorized)
+            }
+            _ => {}
+        }
+
+        let received = Received {
+            rate_limit: RateLimit::from_headers(response.headers()),
+            first_byte,
+        };
+        let text = response.text().map_err(|_| ProviderError::Connection)?;
+        Ok((text, received))
+    }
+}
+
+/// The requests sent to a provider within its quota window.
+struct Budget<'a> {
+    provider: &'a providers::Provider,
+    quota: Option<Quota>,
+    sent: VecDeque<Instant>,
+    used: u64,
+}
+
+impl<'a> Budget<'a> {
+    fn new(provider: &'a providers::Provider, quota: Option<Quota>) -> Budget<'a> {
+        Budget {
+            provider,
+            quota,
+            sent: VecDeque::new(),
+            used: 0,
+        }
+    }
+
+    /// Returns when the provider is able to accept the next request.
+    fn available_at(&mut self, now: Instant) -> Instant {
+        let quota = match self.quota {
+            Some(quota) => quota,
+            None => return now,
+        };
+        while matches!(self.sent.front(), Some(&at) if at + quota.window <= now) {
+            self.sent.pop_front();
+        }
+        match self.sent.front() {
+            Some(&first) if self.sent.len() >= quota.requests as usize => first + quota.window,
+            _ => now,
+        }
+    }
+
+    fn spend(&mut self, at: Instant) {
+        self.used += 1;
+        if self.quota.is_some() {
+            self.sent.push_back(at);
+        }
+    }
+}
+
+/// Returns how long the provider asks to wait with the `Retry-After` header,
+/// in seconds, at most `MAX_RETRY_AFTER`.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|r| r.to_str().ok())
+        .and_then(|r| r.trim().parse().ok())
+        .map(|seconds: u64| Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+/// Returns the time left until the deadline, if there is one.
+fn time_left(deadline: Option<Instant>) -> Option<Duration> {
+    deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Returns whether the error tells the provider is rate limited or
+/// unavailable, so that another provider should be tried.
+fn is_overloaded(error: ProviderError) -> bool {
+    matches!(
+        error.kind(),
+        ProviderError::RateLimited | ProviderError::Unavailable
+    )
+}
+
+/// Returns the location the response redirects to: with an HTTP redirect,
+/// a `Refresh` header or a meta refresh tag of an HTML page.
+fn redirect_target(response: reqwest::blocking::Response) -> Option<String> {
+    // The meta refresh tags are in the head of the page, there is no need to
+    // read the pages further than that.
+    const MAX_HEAD_SIZE: u64 = 64 * 1024;
+
+    let header = |name| response.headers().get(name).and_then(|h| h.to_str().ok());
+    if response.status().is_redirection() {
+        return header(LOCATION).map(String::from);
+    }
+    if let Some(target) = header(REFRESH).and_then(expand::refresh_target) {
+        return Some(target.to_owned());
+    }
+    match header(CONTENT_TYPE) {
+        Some(content_type) if content_type.contains("html") => {}
+        _ => return None,
+    }
+
+    let mut head = Vec::new();
+    response.take(MAX_HEAD_SIZE).read_to_end(&mut head).ok()?;
+    expand::meta_refresh(&String::from_utf8_lossy(&head)).map(String::from)
+}
+
+/// Replaces the scheme and the host of the URL with the base URL, keeping its
+/// path and query.
+fn rebase(url: &str, base_url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = rest.find(['/', '?']).map_or("", |i| &rest[i..]);
+    format!("{}{}", base_url.trim_end_matches('/'), path)
+}
+
+/// Generates an identifier which is unique within the process and unlikely
+/// to repeat across the processes.
+fn new_correlation_id() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed) as u64;
+    format!(
+        "{:016x}-{:08x}-{:04x}",
+        nanos,
+        std::process::id(),
+        count & 0xffff
+    )
+}
+
+/// A set of `UrlShortener`s, each with its own http client and connection
+/// pool, across which the calls are distributed in a round-robin manner.
+///
+/// It is meant for the threaded applications which saturate the connection
+/// pool of a single client, for example during batch jobs.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use std::thread;
+/// use urlshortener::{client::ShortenerPool, providers::Provider};
+///
+/// let pool = Arc::new(ShortenerPool::new(4).unwrap());
+/// let handles: Vec<_> = (0..8)
+///     .map(|i| {
+///         let pool = Arc::clone(&pool);
+///         thread::spawn(move || pool.generate(format!("https://rust-lang.org/{}", i), &Provider::IsGd))
+///     })
+///     .collect();
+/// for handle in handles {
+///     let _short_url = handle.join().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ShortenerPool {
+    shorteners: Vec<UrlShortener>,
+    next: AtomicUsize,
+}
+
+impl ShortenerPool {
+    /// Creates new `ShortenerPool` of `size` shorteners with the default
+    /// (3 seconds) timeout. At least one shortener is always created.
+    pub fn new(size: usize) -> Result<ShortenerPool, reqwest::Error> {
+        let shorteners = (0..size.max(1))
+            .map(|_| UrlShortener::new())
+            .collect::<Result<_, _>>()?;
+        Ok(ShortenerPool::with_shorteners(shorteners))
+    }
+
+    /// Creates new `ShortenerPool` of the configured shorteners. For the
+    /// pool to be of any use, they must not share their http clients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shorteners` is empty.
+    pub fn with_shorteners(shorteners: Vec<UrlShortener>) -> ShortenerPool {
+        assert!(!shorteners.is_empty(), "a shortener pool can't be empty");
+        ShortenerPool {
+            shorteners,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of shorteners in the pool.
+    pub fn size(&self) -> usize {
+        self.shorteners.len()
+    }
+
+    /// Returns the next shortener to use, and advances the pool.
+    pub fn get(&self) -> &UrlShortener {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.shorteners.len();
+        &self.shorteners[index]
+    }
+
+    /// Attempts to get a short URL using the specified provider, with the
+    /// next shortener of the pool. See `UrlShortener::generate`.
+    pub fn generate<S: AsRef<str>>(
+        &self,
+        url: S,
+        provider: &providers::Provider,
+    ) -> Result<String, ProviderError> {
+        self.get().generate(url, provider)
+    }
+
+    /// Attempts to get a short URL using the specified provider and the
+    /// additional link options, with the next shortener of the pool. See
+    /// `UrlShortener::generate_with_options`.
+    pub fn generate_with_options<S: AsRef<str>>(
+        &self,
+        url: S,
+        provider: &providers::Provider,
+        options: &ShortenOptions,
+    ) -> Result<String, ProviderError> {
+        self.get().generate_with_options(url, provider, options)
+    }
+}
+
+/// Returns the options to build the request with: the preview is not a part
+/// of the request for the providers where `UrlShortener::parse` turns the
+/// short URL into the preview one.
+fn request_options<'a>(
+    provider: &providers::Provider,
+    options: &'a ShortenOptions,
+) -> Cow<'a, ShortenOptions> {
+    if options.preview && provider.previews_with_hyphen() {
+        let mut options = options.clone();
+        options.clear(OptionKind::Preview);
+        Cow::Owned(options)
+    } else {
+        Cow::Borrowed(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client;
+    use crate::options::{SchemePolicy, ShortenOptions};
+    use crate::providers;
+    use reqwest::header::HeaderMap;
+    use std::time::Duration;
+
+    #[test]
+    fn rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client::RateLimit::from_headers(&headers), None);
+
+        headers.insert("X-RateLimit-Remaining", "42".parse().unwrap());
+        headers.insert("RateLimit-Reset", "60".parse().unwrap());
+        assert_eq!(
+            client::RateLimit::from_headers(&headers),
+            Some(client::RateLimit {
+                limit: None,
+                remaining: Some(42),
+                reset: Some(60),
+            })
+        );
+    }
+
+    #[test]
+    fn rebase_keeps_path_and_query() {
+        let base = "http://localhost:9000/mock/";
+        assert_eq!(
+            client::rebase("https://api-ssl.bitly.com/v4/shorten", base),
+            "http://localhost:9000/mock/v4/shorten"
+        );
+        assert_eq!(
+            client::rebase("https://is.gd?format=json", base),
+            "http://localhost:9000/mock?format=json"
+        );
+        assert_eq!(
+            client::rebase("https://clck.ru", base),
+            "http://localhost:9000/mock"
+        );
+    }
+
+    #[test]
+    fn debug_hides_header_values() {
+        let mut headers = HeaderMap::new();
+        headers.insert("CF-Access-Client-Secret", "MY_SECRET".parse().unwrap());
+        let us = client::UrlShortener::new()
+            .unwrap()
+            .with_default_headers(headers.clone())
+            .with_provider_headers("kutt.it", headers);
+        let debug = format!("{:?}", us);
+        assert!(debug.contains("cf-access-client-secret"));
+        assert!(!debug.contains("MY_SECRET"));
+    }
+
+    #[test]
+    fn gd_previews() {
+        let us = client::UrlShortener::new().unwrap();
+        let options = ShortenOptions::new().preview(true);
+        let provider = providers::Provider::IsGd;
+
+        let req = us
+            .generate_dry_run("https://rust-lang.org", &provider, &options)
+            .unwrap();
+        assert!(req.url.starts_with("https://is.gd/create.php?"));
+        let res = r#"{ "shorturl": "https://is.gd/MOgh5q" }"#;
+        assert_eq!(
+            us.parse(res, &provider, &options),
+            Ok("https://is.gd/MOgh5q-".to_owned())
+        );
+        assert_eq!(
+            us.parse(res, &provider, &ShortenOptions::default()),
+            Ok("https://is.gd/MOgh5q".to_owned())
+        );
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client::retry_after(&headers), None);
+        headers.insert("Retry-After", "120".parse().unwrap());
+        assert_eq!(
+            client::retry_after(&headers),
+            Some(Duration::from_secs(120))
+        );
+        headers.insert("Retry-After", u64::MAX.to_string().parse().unwrap());
+        assert_eq!(client::retry_after(&headers), Some(client::MAX_RETRY_AFTER));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn cooling_down_providers_are_skipped() {
+        let fake = |pattern: &str| providers::Provider::Fake {
+            pattern: pattern.to_owned(),
+        };
+        let (first, second) = (fake("https://a.link"), fake("https://b.link"));
+        let us = client::UrlShortener::new()
+            .unwrap()
+            .with_deadline(Duration::MAX);
+
+        us.cool_down(&first, Duration::MAX);
+        assert!(us.is_cooling_down(&first));
+        assert!(!us.is_cooling_down(&second));
+
+        let short_url = us.try_generate("https://rust-lang.org", Some(&[first.clone(), second]));
+        assert!(short_url.unwrap().starts_with("https://b.link/"));
+        assert_eq!(
+            us.try_generate("https://rust-lang.org", Some(&[first])),
+            Err(providers::ProviderError::RateLimited)
+        );
+    }
+
+    #[test]
+    fn destination_check_skips_other_schemes() {
+        let us = client::UrlShortener::new()
+            .unwrap()
+            .with_destination_check(true);
+        let provider = providers::Provider::Fake {
+            pattern: "https://fake.link".to_owned(),
+        };
+        let options = ShortenOptions::new().schemes(SchemePolicy::Any);
+        let res = us.generate_with_options("magnet:?xt=urn:btih:abc", &provider, &options);
+        assert!(res.is_ok(), "{:?}", res);
+    }
 
     /// This test does not cover services which require authentication for obvious reasons.
     #[test]
@@ -0,0 +1,148 @@
+use crate::providers::{Provider, ProviderError};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A credential string, which is never shown by its `Debug` implementation.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Creates new `Secret` from the credential string.
+    pub fn new<S: Into<String>>(secret: S) -> Secret {
+        Secret(secret.into())
+    }
+
+    /// Returns the credential string.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Secret {
+        Secret(secret)
+    }
+}
+
+impl<'a> From<&'a str> for Secret {
+    fn from(secret: &'a str) -> Secret {
+        Secret(secret.to_owned())
+    }
+}
+
+/// Supplies the credentials of the providers at the time the requests are
+/// built, instead of the ones stored in the `Provider` variants.
+///
+/// The token replaces the api key, token or signature of the provider; the
+/// other provider settings, like the host or the domain, are kept.
+pub trait CredentialProvider {
+    /// Returns the credential to use for the next request to the provider.
+    fn token(&self, provider: &Provider) -> Result<Secret, ProviderError>;
+}
+
+impl CredentialProvider for Secret {
+    fn token(&self, _: &Provider) -> Result<Secret, ProviderError> {
+        Ok(self.clone())
+    }
+}
+
+/// A credential which expires and is obtained again when needed, for example
+/// an OAuth2 access token obtained with a refresh token.
+///
+/// The credentials are kept per provider, so that the same instance may
+/// supply several providers.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use urlshortener::{
+///     credentials::{CredentialProvider, RefreshingToken, Secret},
+///     providers::Provider,
+/// };
+///
+/// let credentials = RefreshingToken::new(|_: &Provider| {
+///     // Exchange the refresh token for a new access token here.
+///     Ok((Secret::new("MY_ACCESS_TOKEN"), Duration::from_secs(3600)))
+/// });
+/// let provider = Provider::BitLy { token: String::new() };
+/// assert_eq!(credentials.token(&provider).unwrap().expose(), "MY_ACCESS_TOKEN");
+/// ```
+pub struct RefreshingToken<F> {
+    refresh: F,
+    // The credentials valid for longer than an `Instant` may represent never
+    // expire.
+    current: Mutex<HashMap<Provider, (Secret, Option<Instant>)>>,
+}
+
+impl<F> RefreshingToken<F>
+where
+    F: Fn(&Provider) -> Result<(Secret, Duration), ProviderError>,
+{
+    /// Creates new `RefreshingToken` which obtains the credentials with the
+    /// `refresh` function. The function returns the credential along with
+    /// the time it stays valid for.
+    pub fn new(refresh: F) -> RefreshingToken<F> {
+        RefreshingToken {
+            refresh,
+            current: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<F> fmt::Debug for RefreshingToken<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RefreshingToken").finish()
+    }
+}
+
+impl<F> CredentialProvider for RefreshingToken<F>
+where
+    F: Fn(&Provider) -> Result<(Secret, Duration), ProviderError>,
+{
+    fn token(&self, provider: &Provider) -> Result<Secret, ProviderError> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+
+        match current.get(provider) {
+            Some((ref secret, Some(until))) if Instant::now() < *until => return Ok(secret.clone()),
+            Some((ref secret, None)) => return Ok(secret.clone()),
+            _ => {}
+        }
+
+        let (secret, valid_for) = (self.refresh)(provider)?;
+        let valid_until = Instant::now().checked_add(valid_for);
+        current.insert(provider.clone(), (secret.clone(), valid_until));
+        Ok(secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refreshing_tokens_are_per_provider() {
+        let credentials = RefreshingToken::new(|provider: &Provider| {
+            Ok((Secret::new(provider.to_name()), Duration::from_secs(3600)))
+        });
+        let bitly = Provider::BitLy {
+            token: String::new(),
+        };
+        let sniply = Provider::SnipLy {
+            token: String::new(),
+            cta: None,
+            campaign: None,
+        };
+        assert_eq!(credentials.token(&bitly).unwrap().expose(), "bitly.com");
+        assert_eq!(credentials.token(&sniply).unwrap().expose(), "snip.ly");
+        assert_eq!(credentials.token(&bitly).unwrap().expose(), "bitly.com");
+    }
+}
@@ -0,0 +1,119 @@
+use std::borrow::Borrow;
+
+const HEX: &[u8] = b"0123456789ABCDEF";
+
+/// Appends the `%XX` encoding of the byte.
+fn push_encoded(encoded: &mut String, byte: u8) {
+    encoded.push('%');
+    encoded.push(char::from(HEX[usize::from(byte >> 4)]));
+    encoded.push(char::from(HEX[usize::from(byte & 0x0f)]));
+}
+
+/// Percent-encodes the bytes as in the `application/x-www-form-urlencoded`
+/// format: the ASCII alphanumerics and `*-._` are kept, the space becomes
+/// `+` and every other byte becomes `%XX`.
+pub(crate) fn byte_serialize(input: &[u8]) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for &byte in input {
+        match byte {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'*' | b'-' | b'.' | b'_' => {
+                encoded.push(char::from(byte))
+            }
+            b' ' => encoded.push('+'),
+            _ => push_encoded(&mut encoded, byte),
+        }
+    }
+    encoded
+}
+
+/// Percent-encodes the string as a single path segment: the unreserved
+/// characters (ASCII alphanumerics and `-._~`) are kept and every other byte,
+/// the `/` included, becomes `%XX`.
+pub(crate) fn path_segment(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for &byte in input.as_bytes() {
+        match byte {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(char::from(byte))
+            }
+            _ => push_encoded(&mut encoded, byte),
+        }
+    }
+    encoded
+}
+
+/// Builds an `application/x-www-form-urlencoded` string of name-value pairs,
+/// for the query strings and the form bodies of the requests.
+#[derive(Debug)]
+pub(crate) struct Serializer {
+    target: String,
+    start: usize,
+}
+
+impl Serializer {
+    /// Creates new `Serializer` appending the pairs to the target.
+    pub(crate) fn new(target: String) -> Serializer {
+        let start = target.len();
+        Serializer { target, start }
+    }
+
+    /// Appends the name-value pair.
+    pub(crate) fn append_pair(&mut self, name: &str, value: &str) -> &mut Serializer {
+        if self.target.len() > self.start {
+            self.target.push('&');
+        }
+        self.target.push_str(&byte_serialize(name.as_bytes()));
+        self.target.push('=');
+        self.target.push_str(&byte_serialize(value.as_bytes()));
+        self
+    }
+
+    /// Appends every name-value pair of the iterator.
+    pub(crate) fn extend_pairs<I, K, V>(&mut self, pairs: I) -> &mut Serializer
+    where
+        I: IntoIterator,
+        I::Item: Borrow<(K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        for pair in pairs {
+            let (ref name, ref value) = *pair.borrow();
+            self.append_pair(name.as_ref(), value.as_ref());
+        }
+        self
+    }
+
+    /// Returns the target with the pairs appended, leaving the serializer
+    /// empty.
+    pub(crate) fn finish(&mut self) -> String {
+        self.start = 0;
+        std::mem::take(&mut self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_encoding() {
+        assert_eq!(
+            byte_serialize("a b&c=d/é~*-._".as_bytes()),
+            "a+b%26c%3Dd%2F%C3%A9%7E*-._"
+        );
+        let query = Serializer::new(String::new())
+            .append_pair("url", "https://rust-lang.org/?a=1&b=2")
+            .extend_pairs(&[("format", "json")])
+            .finish();
+        assert_eq!(
+            query,
+            "url=https%3A%2F%2Frust-lang.org%2F%3Fa%3D1%26b%3D2&format=json"
+        );
+    }
+
+    #[test]
+    fn path_segment_encoding() {
+        assert_eq!(path_segment("bit.ly/2Ewn0ZT"), "bit.ly%2F2Ewn0ZT");
+        assert_eq!(path_segment("a b?c#d~é"), "a%20b%3Fc%23d~%C3%A9");
+    }
+}
@@ -0,0 +1,236 @@
+use crate::providers;
+use std::time::SystemTime;
+
+/// A single step of a redirect chain.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Hop {
+    /// The URL requested.
+    pub url: String,
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// The `Content-Type` of the response, if any.
+    pub content_type: Option<String>,
+}
+
+/// Describes why a redirect chain ended.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ChainEnd {
+    /// The last URL responded without redirecting.
+    Destination,
+    /// The last URL redirected to a URL visited before.
+    Loop,
+    /// The chain reached the maximum number of hops.
+    TooLong,
+}
+
+/// The redirects followed from a short URL to its destination.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::expand::{ChainEnd, Hop, RedirectChain};
+///
+/// let chain = RedirectChain {
+///     hops: vec![
+///         Hop { url: "https://bit.ly/a1".to_owned(), status: 301, content_type: None },
+///         Hop { url: "https://is.gd/b2".to_owned(), status: 301, content_type: None },
+///         Hop {
+///             url: "https://rust-lang.org/".to_owned(),
+///             status: 200,
+///             content_type: Some("text/html".to_owned()),
+///         },
+///     ],
+///     end: ChainEnd::Destination,
+/// };
+/// assert_eq!(chain.final_url(), Some("https://rust-lang.org/"));
+/// assert!(chain.is_multi_shortened());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RedirectChain {
+    /// The URLs requested, starting with the short URL.
+    pub hops: Vec<Hop>,
+    /// Why the chain ended.
+    pub end: ChainEnd,
+}
+
+impl RedirectChain {
+    /// Returns the last URL of the chain, which is the destination if the
+    /// chain ended with `ChainEnd::Destination`.
+    pub fn final_url(&self) -> Option<&str> {
+        self.hops.last().map(|hop| hop.url.as_str())
+    }
+
+    /// Returns the number of the hops on the domains of the known URL
+    /// shortener services (see `providers::is_short_url`).
+    pub fn shortener_hops(&self) -> usize {
+        self.hops
+            .iter()
+            .filter(|hop| providers::is_short_url(&hop.url))
+            .count()
+    }
+
+    /// Returns whether the destination was shortened more than once, which
+    /// is a common way to hide it.
+    pub fn is_multi_shortened(&self) -> bool {
+        self.shortener_hops() > 1
+    }
+}
+
+/// The destination of a short URL along with how it was reached, for
+/// deciding whether the short URL is safe to follow.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ExpandedUrl {
+    /// The last URL reached, which is the destination if the chain ended
+    /// with `ChainEnd::Destination`.
+    pub final_url: String,
+    /// The HTTP status of the last URL.
+    pub status: u16,
+    /// The URLs requested, starting with the short URL and ending with the
+    /// final URL.
+    pub hops: Vec<Hop>,
+    /// The `Content-Type` of the last URL, if any.
+    pub content_type: Option<String>,
+    /// Why the redirects stopped being followed.
+    pub end: ChainEnd,
+    /// When the short URL was expanded.
+    pub resolved_at: SystemTime,
+}
+
+impl ExpandedUrl {
+    /// Creates new `ExpandedUrl` from the redirect chain followed at the
+    /// given time. Returns `None` if the chain has no hops.
+    pub fn from_chain(chain: RedirectChain, resolved_at: SystemTime) -> Option<ExpandedUrl> {
+        let last = chain.hops.last()?.clone();
+        Some(ExpandedUrl {
+            final_url: last.url,
+            status: last.status,
+            hops: chain.hops,
+            content_type: last.content_type,
+            end: chain.end,
+            resolved_at,
+        })
+    }
+}
+
+/// Returns the URL a page redirects to with a `<meta http-equiv="refresh">`
+/// tag, as the preview pages of some URL shorteners do instead of an HTTP
+/// redirect. The URL is returned as written, it may be relative to the page.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::expand;
+///
+/// let html = r#"<html><head><META HTTP-EQUIV="Refresh" CONTENT="3; URL='https://rust-lang.org/'"></head></html>"#;
+/// assert_eq!(expand::meta_refresh(html), Some("https://rust-lang.org/"));
+/// assert_eq!(expand::meta_refresh("<meta charset=\"utf-8\">"), None);
+/// ```
+pub fn meta_refresh(html: &str) -> Option<&str> {
+    let lowercase = html.to_ascii_lowercase();
+    let mut from = 0;
+
+    while let Some(start) = lowercase[from..].find("<meta").map(|i| from + i) {
+        let end = lowercase[start..]
+            .find('>')
+            .map_or(lowercase.len(), |i| start + i);
+        from = end;
+
+        let tag = &lowercase[start..end];
+        if attribute(tag, "http-equiv").map(str::trim) != Some("refresh") {
+            continue;
+        }
+        let content = match attribute(tag, "content") {
+            Some(content) => content,
+            None => continue,
+        };
+        // The offsets are the same in the lowercase copy and in the page.
+        let offset = start + (content.as_ptr() as usize - tag.as_ptr() as usize);
+        return refresh_target(&html[offset..offset + content.len()]);
+    }
+    None
+}
+
+/// Returns the URL of a refresh instruction, the value of the `content`
+/// attribute of a meta refresh tag or of the `Refresh` HTTP header, which is
+/// the delay optionally followed by the URL: `5; url=https://rust-lang.org`.
+pub(crate) fn refresh_target(content: &str) -> Option<&str> {
+    let position = content.find([';', ','])?;
+    let target = content[position + 1..].trim_start();
+    let target = match target.get(..3) {
+        Some(name) if name.eq_ignore_ascii_case("url") => target[3..].trim_start(),
+        _ => target,
+    };
+    let target = match target.strip_prefix('=') {
+        Some(target) => target.trim(),
+        None => target.trim(),
+    };
+    let target = target.trim_matches(|c| c == '\'' || c == '"').trim();
+
+    if target.is_empty() {
+        None
+    } else {
+        Some(target)
+    }
+}
+
+/// Returns the value of the attribute of the HTML tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut from = 0;
+
+    while let Some(start) = tag[from..].find(name).map(|i| from + i) {
+        from = start + name.len();
+        let preceded = tag[..start].ends_with(|c: char| c.is_ascii_whitespace());
+        let rest = tag[from..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(value) if preceded => value.trim_start(),
+            _ => continue,
+        };
+
+        return match value.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => {
+                let value = &value[1..];
+                Some(&value[..value.find(quote).unwrap_or(value.len())])
+            }
+            _ => {
+                let end = value
+                    .find(|c: char| c.is_ascii_whitespace())
+                    .unwrap_or(value.len());
+                Some(&value[..end])
+            }
+        };
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meta_refresh_forms() {
+        let pages = [
+            (
+                r#"<meta http-equiv="refresh" content="0;url=https://a.com/x">"#,
+                Some("https://a.com/x"),
+            ),
+            (
+                r#"<meta content='5; URL = /next?a=1' http-equiv='refresh' />"#,
+                Some("/next?a=1"),
+            ),
+            (
+                "<meta http-equiv=refresh content=0;url=https://a.com/>",
+                Some("https://a.com/"),
+            ),
+            (
+                r#"<meta name="refresh" content="0;url=https://a.com">"#,
+                None,
+            ),
+            (r#"<meta http-equiv="refresh" content="30">"#, None),
+            ("<meta http-equiv", None),
+        ];
+        for (page, target) in pages.iter() {
+            assert_eq!(meta_refresh(page), *target, "{}", page);
+        }
+        assert_eq!(refresh_target("1, https://a.com"), Some("https://a.com"));
+    }
+}
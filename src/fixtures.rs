@@ -0,0 +1,635 @@
+use crate::local::MemoryStore;
+use crate::providers::{
+    self, AdfLyAdvert, BitLyError, CuttlyError, IsGdError, KuttError, Provider, ProviderCode,
+    ProviderError, YourlsAuth,
+};
+use std::sync::Arc;
+
+/// The placeholder credential of the providers returned by `providers`.
+pub const KEY: &str = "KEY";
+
+/// A canned response body of a provider along with the outcome of parsing it.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// The provider which responded.
+    pub provider: Provider,
+    /// What the response is, like "a short URL" or "a taken alias".
+    pub description: &'static str,
+    /// The response body.
+    pub body: &'static str,
+    /// The outcome of parsing the response with `providers::parse`.
+    pub expected: Result<&'static str, ProviderError>,
+}
+
+impl Fixture {
+    /// Parses the response body with `providers::parse`, returning the
+    /// description of the mismatch if the outcome is not the expected one.
+    pub fn check(&self) -> Result<(), String> {
+        let parsed = providers::parse(self.body, &self.provider);
+        if parsed.as_deref().map_err(|e| *e) == self.expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}, {}: expected {:?}, parsed {:?}",
+                self.provider.to_name(),
+                self.description,
+                self.expected,
+                parsed
+            ))
+        }
+    }
+}
+
+/// Returns every provider variant, with placeholder settings.
+#[allow(deprecated)]
+pub fn providers() -> Vec<Provider> {
+    let key = || KEY.to_owned();
+    vec![
+        Provider::Abv8,
+        Provider::AdfLy {
+            api_key: key(),
+            user_id: key(),
+            advert_type: AdfLyAdvert::Banner,
+        },
+        Provider::BamBz,
+        Provider::BitLy { token: key() },
+        Provider::BitUrl,
+        Provider::Blink {
+            api_token: key(),
+            domain_id: 1,
+        },
+        Provider::Bmeo,
+        Provider::Branch { key: key() },
+        Provider::ChhotoUrl {
+            api_key: key(),
+            host: "https://chhoto.local".to_owned(),
+        },
+        Provider::CleanUri,
+        Provider::ClckRu,
+        Provider::Cuttly { api_key: key() },
+        Provider::Fake {
+            pattern: "https://fake.link/{hash}".to_owned(),
+        },
+        Provider::FifoCc,
+        Provider::GgGg,
+        Provider::GooGl { api_key: key() },
+        Provider::Kutt {
+            api_key: key(),
+            host: None,
+        },
+        Provider::HecSu,
+        Provider::HmmRs,
+        Provider::IsGd,
+        Provider::LihiCc {
+            token: key(),
+            split_urls: vec![],
+        },
+        Provider::LnnkIn,
+        Provider::Local {
+            base_url: "http://localhost:8080".to_owned(),
+            store: Arc::new(MemoryStore::new()),
+        },
+        Provider::MgnetMe,
+        Provider::N9Cl { api_key: None },
+        Provider::NowLinks,
+        Provider::OneLink {
+            template_id: key(),
+            api_token: key(),
+        },
+        Provider::OuoIo { api_key: key() },
+        Provider::PhxCoIn,
+        Provider::PsbeCo,
+        Provider::PseIs { token: key() },
+        Provider::SnipLy {
+            token: key(),
+            cta: None,
+            campaign: None,
+        },
+        Provider::SpooMe,
+        Provider::SCoop,
+        Provider::Rebrandly {
+            api_key: key(),
+            domain: None,
+        },
+        Provider::Rlu,
+        Provider::ShortIo {
+            api_key: key(),
+            domain: "example.com".to_owned(),
+        },
+        Provider::ShorteSt { api_token: key() },
+        Provider::ShrtcoDe,
+        Provider::SId {
+            client_id: key(),
+            api_key: key(),
+        },
+        Provider::SirBz,
+        Provider::T2m { api_key: key() },
+        Provider::TinyUrl,
+        Provider::TinyUrlApi { token: key() },
+        Provider::TinyPh,
+        Provider::TnyIm,
+        Provider::UrlShortenerIo,
+        Provider::VGd,
+        Provider::VHt,
+        Provider::VurlCom,
+        Provider::Yourls {
+            base_url: "https://yourls.local".to_owned(),
+            auth: YourlsAuth::Public,
+        },
+    ]
+}
+
+/// Returns the representative responses of every provider: a successful one
+/// for each, and the documented errors for the providers which report them.
+#[allow(deprecated)]
+pub fn all() -> Vec<Fixture> {
+    let key = || KEY.to_owned();
+    vec![
+        Fixture {
+            provider: Provider::Abv8,
+            description: "a short URL",
+            body: "https://abv8.me/4Ac",
+            expected: Ok("https://abv8.me/4Ac"),
+        },
+        Fixture {
+            provider: Provider::AdfLy {
+                api_key: key(),
+                user_id: key(),
+                advert_type: AdfLyAdvert::Interstitial,
+            },
+            description: "a short URL",
+            body: "https://adf.ly/1a2B3c",
+            expected: Ok("https://adf.ly/1a2B3c"),
+        },
+        Fixture {
+            provider: Provider::AdfLy {
+                api_key: key(),
+                user_id: key(),
+                advert_type: AdfLyAdvert::Interstitial,
+            },
+            description: "an error",
+            body: "error",
+            expected: Err(ProviderError::Deserialize),
+        },
+        Fixture {
+            provider: Provider::BamBz,
+            description: "a short URL",
+            body: r#"{"error":0,"url":"https:\/\/bam.bz\/1aB"}"#,
+            expected: Ok("https://bam.bz/1aB"),
+        },
+        Fixture {
+            provider: Provider::BitLy { token: key() },
+            description: "a short URL",
+            body: r#"{"created_at":"2024-01-01T00:00:00+0000","id":"bit.ly/2Ewn0ZT","link":"https://bit.ly/2Ewn0ZT","long_url":"https://rust-lang.org/"}"#,
+            expected: Ok("https://bit.ly/2Ewn0ZT"),
+        },
+        Fixture {
+            provider: Provider::BitLy { token: key() },
+            description: "an invalid URL",
+            body: r#"{"message":"INVALID_ARG_LONG_URL","resource":"bitlinks","description":"The value provided is invalid."}"#,
+            expected: Err(ProviderError::Provider(ProviderCode::BitLy(
+                BitLyError::InvalidLongUrl,
+            ))),
+        },
+        Fixture {
+            provider: Provider::BitUrl,
+            description: "a short URL",
+            body: r#"{"code":1,"short":"https://biturl.top/Yjuiu2"}"#,
+            expected: Ok("https://biturl.top/Yjuiu2"),
+        },
+        Fixture {
+            provider: Provider::Blink {
+                api_token: key(),
+                domain_id: 1,
+            },
+            description: "a short URL",
+            body: r#"{"objects":[{"short_link":"https://bl.ink/a1"}]}"#,
+            expected: Ok("https://bl.ink/a1"),
+        },
+        Fixture {
+            provider: Provider::Bmeo,
+            description: "a short URL",
+            body: r#"{"short":"http://bmeo.org/a1"}"#,
+            expected: Ok("http://bmeo.org/a1"),
+        },
+        Fixture {
+            provider: Provider::Branch { key: key() },
+            description: "a short URL",
+            body: r#"{"url":"https://example.app.link/a1"}"#,
+            expected: Ok("https://example.app.link/a1"),
+        },
+        Fixture {
+            provider: Provider::ChhotoUrl {
+                api_key: key(),
+                host: "https://chhoto.local".to_owned(),
+            },
+            description: "a short URL",
+            body: r#"{"success":true,"error":false,"shorturl":"https://chhoto.local/a1"}"#,
+            expected: Ok("https://chhoto.local/a1"),
+        },
+        Fixture {
+            provider: Provider::ChhotoUrl {
+                api_key: key(),
+                host: "https://chhoto.local".to_owned(),
+            },
+            description: "a taken alias",
+            body: r#"{"success":false,"error":true,"reason":"Short URL already exists!"}"#,
+            expected: Err(ProviderError::AliasTaken),
+        },
+        Fixture {
+            provider: Provider::CleanUri,
+            description: "a short URL",
+            body: r#"{"result_url":"https:\/\/cleanuri.com\/a1"}"#,
+            expected: Ok("https://cleanuri.com/a1"),
+        },
+        Fixture {
+            provider: Provider::ClckRu,
+            description: "a short URL",
+            body: "https://clck.ru/a1",
+            expected: Ok("https://clck.ru/a1"),
+        },
+        Fixture {
+            provider: Provider::Cuttly { api_key: key() },
+            description: "a short URL",
+            body: r#"{"url":{"status":7,"fullLink":"https:\/\/rust-lang.org","shortLink":"https:\/\/cutt.ly\/a1"}}"#,
+            expected: Ok("https://cutt.ly/a1"),
+        },
+        Fixture {
+            provider: Provider::Cuttly { api_key: key() },
+            description: "a taken alias",
+            body: r#"{"url":{"status":3}}"#,
+            expected: Err(ProviderError::Provider(ProviderCode::Cuttly(
+                CuttlyError::AliasTaken,
+            ))),
+        },
+        Fixture {
+            provider: Provider::Cuttly { api_key: key() },
+            description: "an invalid api key",
+            body: r#"{"url":{"status":4}}"#,
+            expected: Err(ProviderError::Provider(ProviderCode::Cuttly(
+                CuttlyError::InvalidApiKey,
+            ))),
+        },
+        Fixture {
+            provider: Provider::Fake {
+                pattern: "https://fake.link/{hash}".to_owned(),
+            },
+            description: "any response, the provider sends no request",
+            body: "https://fake.link/a1",
+            expected: Err(ProviderError::Unsupported),
+        },
+        Fixture {
+            provider: Provider::FifoCc,
+            description: "a short URL",
+            body: r#"{"shortner":"a1B"}"#,
+            expected: Ok("http://fifo.cc/a1B"),
+        },
+        Fixture {
+            provider: Provider::GgGg,
+            description: "a short URL",
+            body: "http://gg.gg/a1",
+            expected: Ok("http://gg.gg/a1"),
+        },
+        Fixture {
+            provider: Provider::GooGl { api_key: key() },
+            description: "any response",
+            body: r#"{"id":"https://goo.gl/a1"}"#,
+            expected: Err(ProviderError::Discontinued),
+        },
+        Fixture {
+            provider: Provider::Kutt {
+                api_key: key(),
+                host: None,
+            },
+            description: "a short URL",
+            body: r#"{"id":"00000000-0000-0000-0000-000000000000","address":"a1","link":"https://kutt.it/a1","target":"https://rust-lang.org"}"#,
+            expected: Ok("https://kutt.it/a1"),
+        },
+        Fixture {
+            provider: Provider::Kutt {
+                api_key: key(),
+                host: None,
+            },
+            description: "a taken alias",
+            body: r#"{"error":"Custom URL is already in use."}"#,
+            expected: Err(ProviderError::Provider(ProviderCode::Kutt(
+                KuttError::AliasTaken,
+            ))),
+        },
+        Fixture {
+            provider: Provider::HecSu,
+            description: "a short URL",
+            body: "<result><short>http://hec.su/a1</short></result>",
+            expected: Ok("http://hec.su/a1"),
+        },
+        Fixture {
+            provider: Provider::HmmRs,
+            description: "a short URL",
+            body: r#"{"shortUrl":"https://hmm.rs/a1"}"#,
+            expected: Ok("https://hmm.rs/a1"),
+        },
+        Fixture {
+            provider: Provider::IsGd,
+            description: "a short URL",
+            body: r#"{ "shorturl": "https://is.gd/MOgh5q" }"#,
+            expected: Ok("https://is.gd/MOgh5q"),
+        },
+        Fixture {
+            provider: Provider::IsGd,
+            description: "an invalid URL",
+            body: r#"{ "errorcode": 1, "errormessage": "Please enter a valid URL to shorten" }"#,
+            expected: Err(ProviderError::Provider(ProviderCode::IsGd(
+                IsGdError::InvalidUrl,
+            ))),
+        },
+        Fixture {
+            provider: Provider::IsGd,
+            description: "an exceeded rate limit",
+            body: r#"{ "errorcode": 3, "errormessage": "Rate limit exceeded" }"#,
+            expected: Err(ProviderError::Provider(ProviderCode::IsGd(
+                IsGdError::RateLimited,
+            ))),
+        },
+        Fixture {
+            provider: Provider::LihiCc {
+                token: key(),
+                split_urls: vec![],
+            },
+            description: "a short URL",
+            body: r#"{"short_url":"https://lihi.cc/a1"}"#,
+            expected: Ok("https://lihi.cc/a1"),
+        },
+        Fixture {
+            provider: Provider::LnnkIn,
+            description: "a short URL",
+            body: r#"{"short_url":"https://lnnk.in/a1"}"#,
+            expected: Ok("https://lnnk.in/a1"),
+        },
+        Fixture {
+            provider: Provider::Local {
+                base_url: "http://localhost:8080".to_owned(),
+                store: Arc::new(MemoryStore::new()),
+            },
+            description: "any response, the provider sends no request",
+            body: "http://localhost:8080/a1",
+            expected: Err(ProviderError::Unsupported),
+        },
+        Fixture {
+            provider: Provider::MgnetMe,
+            description: "a short URL",
+            body: r#"{"state":"success","shorturl":"http://mgnet.me/a1"}"#,
+            expected: Ok("http://mgnet.me/a1"),
+        },
+        Fixture {
+            provider: Provider::MgnetMe,
+            description: "an invalid magnet link",
+            body: r#"{"state":"error","message":"invalid magnet"}"#,
+            expected: Err(ProviderError::InvalidUrl),
+        },
+        Fixture {
+            provider: Provider::N9Cl { api_key: None },
+            description: "a short URL",
+            body: r#"{"short_url":"https://n9.cl/a1"}"#,
+            expected: Ok("https://n9.cl/a1"),
+        },
+        Fixture {
+            provider: Provider::NowLinks,
+            description: "a short URL",
+            body: "http://nowlinks.net/a1",
+            expected: Ok("http://nowlinks.net/a1"),
+        },
+        Fixture {
+            provider: Provider::OneLink {
+                template_id: key(),
+                api_token: key(),
+            },
+            description: "a short URL",
+            body: "https://app.onelink.me/a1/b2",
+            expected: Ok("https://app.onelink.me/a1/b2"),
+        },
+        Fixture {
+            provider: Provider::OuoIo { api_key: key() },
+            description: "a short URL",
+            body: "https://ouo.io/a1",
+            expected: Ok("https://ouo.io/a1"),
+        },
+        Fixture {
+            provider: Provider::PhxCoIn,
+            description: "a short URL",
+            body: "http://phx.co.in/a1",
+            expected: Ok("http://phx.co.in/a1"),
+        },
+        Fixture {
+            provider: Provider::PsbeCo,
+            description: "a short URL",
+            body: "<result><ShortUrl>http://psbe.co/a1</ShortUrl></result>",
+            expected: Ok("http://psbe.co/a1"),
+        },
+        Fixture {
+            provider: Provider::PseIs { token: key() },
+            description: "a short URL",
+            body: r#"{"data":{"picseeUrl":"https://pse.is/a1"}}"#,
+            expected: Ok("https://pse.is/a1"),
+        },
+        Fixture {
+            provider: Provider::SnipLy {
+                token: key(),
+                cta: None,
+                campaign: None,
+            },
+            description: "a short URL",
+            body: r#"{"href":"https://snip.ly/a1"}"#,
+            expected: Ok("https://snip.ly/a1"),
+        },
+        Fixture {
+            provider: Provider::SpooMe,
+            description: "a short URL",
+            body: r#"{"short_url":"https://spoo.me/a1"}"#,
+            expected: Ok("https://spoo.me/a1"),
+        },
+        Fixture {
+            provider: Provider::SpooMe,
+            description: "a taken alias",
+            body: r#"{"AliasError":"Alias already exists"}"#,
+            expected: Err(ProviderError::AliasTaken),
+        },
+        Fixture {
+            provider: Provider::SCoop,
+            description: "a short URL",
+            body: "http://s.coop/a1",
+            expected: Ok("http://s.coop/a1"),
+        },
+        Fixture {
+            provider: Provider::Rebrandly {
+                api_key: key(),
+                domain: None,
+            },
+            description: "a short URL",
+            body: r#"{"id":"a1","shortUrl":"rebrand.ly/a1"}"#,
+            expected: Ok("https://rebrand.ly/a1"),
+        },
+        Fixture {
+            provider: Provider::Rlu,
+            description: "a short URL",
+            body: "http://rlu.ru/a1",
+            expected: Ok("http://rlu.ru/a1"),
+        },
+        Fixture {
+            provider: Provider::ShortIo {
+                api_key: key(),
+                domain: "example.com".to_owned(),
+            },
+            description: "a short URL",
+            body: r#"{"shortURL":"https://example.com/a1"}"#,
+            expected: Ok("https://example.com/a1"),
+        },
+        Fixture {
+            provider: Provider::ShorteSt { api_token: key() },
+            description: "a short URL",
+            body: r#"{"status":"ok","shortenedUrl":"http:\/\/sh.st\/a1"}"#,
+            expected: Ok("http://sh.st/a1"),
+        },
+        Fixture {
+            provider: Provider::ShrtcoDe,
+            description: "a short URL",
+            body: r#"{"ok":true,"result":{"code":"KCveN","full_short_link":"https://shrtco.de/KCveN","full_short_link2":"https://9qr.de/KCveN","full_short_link3":"https://shiny.link/KCveN"}}"#,
+            expected: Ok("https://shrtco.de/KCveN"),
+        },
+        Fixture {
+            provider: Provider::ShrtcoDe,
+            description: "an invalid URL",
+            body: r#"{"ok":false,"error_code":2,"error":"Invalid URL submitted"}"#,
+            expected: Err(ProviderError::InvalidUrl),
+        },
+        Fixture {
+            provider: Provider::SId {
+                client_id: key(),
+                api_key: key(),
+            },
+            description: "a short URL",
+            body: r#"{"short":"a1"}"#,
+            expected: Ok("https://s.id/a1"),
+        },
+        Fixture {
+            provider: Provider::SirBz,
+            description: "a short URL",
+            body: r#"{"short_link":"http://sirbz.com/a1"}"#,
+            expected: Ok("http://sirbz.com/a1"),
+        },
+        Fixture {
+            provider: Provider::T2m { api_key: key() },
+            description: "a short URL",
+            body: r#"{"data":{"short_url":"https://t2m.io/a1","qr_code":"https://t2m.io/a1.png"}}"#,
+            expected: Ok("https://t2m.io/a1"),
+        },
+        Fixture {
+            provider: Provider::TinyUrl,
+            description: "a short URL",
+            body: r#"<button data-clipboard-text="https://tinyurl.com/a1">Copy</button>"#,
+            expected: Ok("https://tinyurl.com/a1"),
+        },
+        Fixture {
+            provider: Provider::TinyUrlApi { token: key() },
+            description: "a short URL",
+            body: r#"{"data":{"tiny_url":"https://tinyurl.com/a1"},"code":0}"#,
+            expected: Ok("https://tinyurl.com/a1"),
+        },
+        Fixture {
+            provider: Provider::TinyPh,
+            description: "a short URL",
+            body: r#"{"hash":"a1"}"#,
+            expected: Ok("http://tiny.ph/a1"),
+        },
+        Fixture {
+            provider: Provider::TnyIm,
+            description: "a short URL",
+            body: r#"{"status":"success","shorturl":"http://tny.im/a1"}"#,
+            expected: Ok("http://tny.im/a1"),
+        },
+        Fixture {
+            provider: Provider::Yourls {
+                base_url: "https://yourls.local".to_owned(),
+                auth: YourlsAuth::Public,
+            },
+            description: "a short URL",
+            body: r#"{"status":"success","shorturl":"https://yourls.local/a1"}"#,
+            expected: Ok("https://yourls.local/a1"),
+        },
+        Fixture {
+            provider: Provider::Yourls {
+                base_url: "https://yourls.local".to_owned(),
+                auth: YourlsAuth::Public,
+            },
+            description: "a taken alias",
+            body: r#"{"status":"fail","code":"error:keyword","message":"Short URL a1 already exists"}"#,
+            expected: Err(ProviderError::AliasTaken),
+        },
+        Fixture {
+            provider: Provider::UrlShortenerIo,
+            description: "a short URL",
+            body: "https://url-shortener.io/a1",
+            expected: Ok("https://url-shortener.io/a1"),
+        },
+        Fixture {
+            provider: Provider::VGd,
+            description: "a short URL",
+            body: r#"{ "shorturl": "https://v.gd/a1" }"#,
+            expected: Ok("https://v.gd/a1"),
+        },
+        Fixture {
+            provider: Provider::VGd,
+            description: "a taken alias",
+            body: r#"{ "errorcode": 2, "errormessage": "The shortened URL you picked already exists" }"#,
+            expected: Err(ProviderError::Provider(ProviderCode::IsGd(
+                IsGdError::InvalidAlias,
+            ))),
+        },
+        Fixture {
+            provider: Provider::VHt,
+            description: "a short URL",
+            body: "http://v.ht/a1",
+            expected: Ok("http://v.ht/a1"),
+        },
+        Fixture {
+            provider: Provider::VurlCom,
+            description: "a short URL",
+            body: "https://vurl.com/a1",
+            expected: Ok("https://vurl.com/a1"),
+        },
+    ]
+}
+
+/// Checks all the fixtures (see `Fixture::check`), returning the
+/// descriptions of the mismatches.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::fixtures;
+///
+/// assert_eq!(fixtures::check_all(), Vec::<String>::new());
+/// ```
+pub fn check_all() -> Vec<String> {
+    all().iter().filter_map(|f| f.check().err()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem;
+
+    #[test]
+    fn every_provider_has_fixtures() {
+        let fixtures = all();
+        for provider in providers() {
+            assert!(
+                fixtures
+                    .iter()
+                    .any(|f| mem::discriminant(&f.provider) == mem::discriminant(&provider)),
+                "no fixture for {}",
+                provider.to_name()
+            );
+        }
+        assert_eq!(check_all(), Vec::<String>::new());
+    }
+}
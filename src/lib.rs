@@ -7,46 +7,73 @@
 //! Creating a short URL via a specified provider is very simple:
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "client")] {
 //! use urlshortener::{providers::Provider, client::UrlShortener};
 //!
 //! let us = UrlShortener::new().unwrap();
 //! let short_url = us.generate("https://my-long-url.com", &Provider::IsGd);
 //! assert!(short_url.is_ok());
+//! # }
 //! ```
 //!
 //! Or attempting all URL shorteners until one is successfully generated:
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "client")] {
 //! use urlshortener::client::UrlShortener;
 //!
 //! let us = UrlShortener::new().unwrap();
 //! let short_url = us.try_generate("https://my-long-url.com", None);
 //! assert!(short_url.is_ok());
+//! # }
 //! ```
 //! In order to use service with authentication use the appropriate provider directly:
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "client")] {
 //! use urlshortener::{ client::UrlShortener, providers::Provider };
 //!
 //! let us = UrlShortener::new().unwrap();
-//! let key = "MY_API_KEY";
-//! let short_url = us.generate("https://my-long-url.com", &Provider::GooGl { api_key:
-//! key.to_owned() });
+//! let token = "MY_TOKEN";
+//! let short_url = us.generate("https://my-long-url.com", &Provider::BitLy { token:
+//! token.to_owned() });
 //! assert!(short_url.is_ok());
+//! # }
 //! ```
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+/// Provider statistics used to order the providers adaptively.
+pub mod adaptive;
+/// Reading and writing the URLs of batch jobs.
+pub mod batch;
 /// A urlshortener http client for performing requests.
 #[cfg(feature = "client")]
 pub mod client;
+/// Credentials supplied to the providers at the request time.
+pub mod credentials;
+mod encode;
+/// Redirect chains followed from the short URLs.
+pub mod expand;
+/// Canned provider responses for testing the parsers offline.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod fixtures;
+/// Short links stored on the provider side.
+pub mod links;
+/// The storage of the links created by the local provider.
+pub mod local;
+/// Per-link options understood by the providers.
+pub mod options;
 pub mod providers;
 /// A request builders for sending via http client.
 pub mod request;
+/// Short link statistics reported by the providers.
+pub mod stats;
 
 /// A prelude module with main useful stuff.
 pub mod prelude {
     #[cfg(feature = "client")]
     pub use crate::client::*;
+    pub use crate::options::ShortenOptions;
     pub use crate::providers::{Provider, PROVIDERS};
 }
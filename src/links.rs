@@ -0,0 +1,104 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A short link stored on the provider side.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Link {
+    /// The provider identifier of the link, used for managing it.
+    pub id: String,
+    /// The short URL.
+    pub short_url: String,
+    /// The URL the short URL redirects to.
+    pub target: String,
+    /// The description of the link, if any.
+    pub description: Option<String>,
+    /// The number of visits of the short URL.
+    pub visits: u64,
+    /// The creation time as reported by the provider.
+    pub created_at: Option<String>,
+    /// The time of the last change as reported by the provider.
+    pub updated_at: Option<String>,
+}
+
+impl Link {
+    /// Returns the time of the last change of the link, or of its creation
+    /// if it has not been changed, when the provider reports it in the RFC
+    /// 3339 format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use urlshortener::links::Link;
+    ///
+    /// let link = Link {
+    ///     created_at: Some("2000-02-29T12:10:45.000Z".to_owned()),
+    ///     updated_at: Some("2000-02-29T14:10:45+02:00".to_owned()),
+    ///     ..Link::default()
+    /// };
+    /// assert_eq!(link.modified_at(), Some(UNIX_EPOCH + Duration::from_secs(951_826_245)));
+    /// ```
+    pub fn modified_at(&self) -> Option<SystemTime> {
+        let created = self.created_at.as_deref().and_then(parse_timestamp);
+        let updated = self.updated_at.as_deref().and_then(parse_timestamp);
+        created.max(updated)
+    }
+}
+
+/// A single page of the links stored on the provider side.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct LinkPage {
+    /// The links of the page.
+    pub links: Vec<Link>,
+    /// The total number of links, if reported by the provider.
+    pub total: Option<u64>,
+}
+
+/// Parses an RFC 3339 timestamp such as `2024-01-01T00:00:00.000Z`, also
+/// accepting the offsets without a colon, `+0000`. The fractions of a second
+/// are ignored.
+pub(crate) fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let number = |from: usize, to: usize| -> Option<i64> {
+        let digits = timestamp.get(from..to)?;
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    };
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let zone = timestamp[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match zone {
+        "Z" | "z" | "" => 0,
+        _ => {
+            let sign = match zone.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let zone = zone[1..].replace(':', "");
+            let hours = zone.get(0..2)?.parse::<i64>().ok()?;
+            let minutes = zone.get(2..4)?.parse::<i64>().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    // Converts the civil date into the days since the epoch, see
+    // <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    u64::try_from(secs)
+        .ok()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
@@ -0,0 +1,177 @@
+use crate::providers::ProviderError;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Mutex, MutexGuard};
+
+/// The alphabet of the generated slugs.
+const BASE62: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A storage of the links created by `Provider::Local`, by slug.
+pub trait LinkStore: Send + Sync {
+    /// Returns the URL stored under the slug.
+    fn get(&self, slug: &str) -> io::Result<Option<String>>;
+    /// Stores the URL under the slug unless the slug is taken, returning
+    /// whether it was stored.
+    fn insert(&self, slug: &str, url: &str) -> io::Result<bool>;
+    /// Returns the number of the stored links.
+    fn count(&self) -> io::Result<u64>;
+}
+
+/// Keeps the links of `Provider::Local` in memory, for the lifetime of the
+/// store.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    links: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryStore {
+    /// Creates new empty `MemoryStore`.
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        // The map is left consistent by every operation.
+        self.links.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl LinkStore for MemoryStore {
+    fn get(&self, slug: &str) -> io::Result<Option<String>> {
+        Ok(self.lock().get(slug).cloned())
+    }
+
+    fn insert(&self, slug: &str, url: &str) -> io::Result<bool> {
+        let mut links = self.lock();
+        if links.contains_key(slug) {
+            return Ok(false);
+        }
+        links.insert(slug.to_owned(), url.to_owned());
+        Ok(true)
+    }
+
+    fn count(&self) -> io::Result<u64> {
+        Ok(self.lock().len() as u64)
+    }
+}
+
+/// Encodes the number with the base62 alphabet: `0-9`, `a-z`, `A-Z`.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::local;
+///
+/// assert_eq!(local::base62(0), "0");
+/// assert_eq!(local::base62(61), "Z");
+/// assert_eq!(local::base62(62), "10");
+/// ```
+pub fn base62(mut number: u64) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(BASE62[(number % 62) as usize]);
+        number /= 62;
+        if number == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().map(|&d| char::from(d)).collect()
+}
+
+/// Stores the URL under the alias, or under the next free generated slug,
+/// returning the short URL under the base URL.
+pub(crate) fn shorten(
+    base_url: &str,
+    store: &dyn LinkStore,
+    url: &str,
+    alias: Option<&str>,
+) -> Result<String, ProviderError> {
+    let short_url = |slug: &str| format!("{}/{}", base_url.trim_end_matches('/'), slug);
+
+    if let Some(alias) = alias {
+        return match store.insert(alias, url) {
+            Ok(true) => Ok(short_url(alias)),
+            Ok(false) => Err(ProviderError::AliasTaken),
+            Err(_) => Err(ProviderError::Unavailable),
+        };
+    }
+
+    // The slugs taken by the aliases are skipped, there are at most as many
+    // of them as there are links.
+    let mut id = store.count().map_err(|_| ProviderError::Unavailable)?;
+    loop {
+        let slug = base62(id);
+        match store.insert(&slug, url) {
+            Ok(true) => return Ok(short_url(&slug)),
+            Ok(false) => id += 1,
+            Err(_) => return Err(ProviderError::Unavailable),
+        }
+    }
+}
+
+/// Returns the URL stored under the slug of the short URL, if the short URL
+/// is under the base URL.
+pub(crate) fn expand(
+    base_url: &str,
+    store: &dyn LinkStore,
+    short_url: &str,
+) -> Result<String, ProviderError> {
+    let slug = short_url
+        .strip_prefix(base_url.trim_end_matches('/'))
+        .and_then(|rest| rest.strip_prefix('/'))
+        .ok_or(ProviderError::InvalidUrl)?;
+
+    match store.get(slug) {
+        Ok(Some(url)) => Ok(url),
+        Ok(None) => Err(ProviderError::InvalidUrl),
+        Err(_) => Err(ProviderError::Unavailable),
+    }
+}
+
+/// Returns the short URL of `Provider::Fake` for the URL: the pattern with
+/// its `{hash}` placeholder replaced by the slug, or with the slug appended
+/// if it has no placeholder. The slug is the alias if given, the base62
+/// encoded FNV-1a hash of the URL otherwise.
+pub(crate) fn fake(pattern: &str, url: &str, alias: Option<&str>) -> String {
+    let slug = match alias {
+        Some(alias) => alias.to_owned(),
+        None => base62(fnv1a(url.as_bytes())),
+    };
+
+    if pattern.contains("{hash}") {
+        pattern.replace("{hash}", &slug)
+    } else {
+        format!("{}/{}", pattern.trim_end_matches('/'), slug)
+    }
+}
+
+/// The 64-bit FNV-1a hash, which is stable across the platforms and the
+/// releases, unlike the hashers of the standard library.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_urls_are_stable() {
+        // The fake short URLs end up in the snapshots of the users, they must
+        // not change between the releases.
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        let short_url = fake("https://fake.link/{hash}", "https://rust-lang.org", None);
+        assert_eq!(short_url, "https://fake.link/dwoEbtq0BRp");
+        assert_eq!(
+            short_url,
+            fake("https://fake.link", "https://rust-lang.org", None)
+        );
+        assert_eq!(
+            fake("https://fake.link/", "a", Some("x")),
+            "https://fake.link/x"
+        );
+    }
+}
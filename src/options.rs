@@ -0,0 +1,483 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Describes when a short URL stops working.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Expiration {
+    /// The short URL expires after the duration since its creation.
+    In(Duration),
+    /// The short URL expires at the given point in time.
+    At(SystemTime),
+}
+
+impl Expiration {
+    /// Returns the duration from now until the expiration, which is zero if
+    /// the expiration time has already passed.
+    pub fn duration_from_now(&self) -> Duration {
+        match *self {
+            Expiration::In(duration) => duration,
+            Expiration::At(time) => time
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO),
+        }
+    }
+
+    /// Returns the point in time of the expiration.
+    pub fn time(&self) -> SystemTime {
+        match *self {
+            Expiration::In(duration) => SystemTime::now() + duration,
+            Expiration::At(time) => time,
+        }
+    }
+
+    /// Formats the expiration time as a `YYYY-MM-DD HH:MM:SS` UTC string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use urlshortener::options::Expiration;
+    ///
+    /// let leap_day = Expiration::At(UNIX_EPOCH + Duration::from_secs(951_826_245));
+    /// assert_eq!(leap_day.to_utc_string(), "2000-02-29 12:10:45");
+    /// ```
+    pub fn to_utc_string(&self) -> String {
+        let secs = self
+            .time()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let (days, secs) = (secs / 86_400, secs % 86_400);
+
+        // Converts the days since the epoch into the civil date, see
+        // <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+        let z = days as i64 + 719_468;
+        let era = z / 146_097;
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        )
+    }
+}
+
+impl From<Duration> for Expiration {
+    fn from(duration: Duration) -> Expiration {
+        Expiration::In(duration)
+    }
+}
+
+impl From<SystemTime> for Expiration {
+    fn from(time: SystemTime) -> Expiration {
+        Expiration::At(time)
+    }
+}
+
+/// Identifies a single option of the `ShortenOptions`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum OptionKind {
+    /// The `ShortenOptions::title` option.
+    Title,
+    /// The `ShortenOptions::tags` option.
+    Tags,
+    /// The `ShortenOptions::alias` option.
+    Alias,
+    /// The `ShortenOptions::expires` option.
+    Expiration,
+    /// The `ShortenOptions::password` option.
+    Password,
+    /// The `ShortenOptions::description` option.
+    Description,
+    /// The `ShortenOptions::domain` option.
+    Domain,
+    /// The `ShortenOptions::reuse_existing` option.
+    ReuseExisting,
+    /// The `ShortenOptions::log_stats` option.
+    LogStats,
+    /// The `ShortenOptions::preview` option.
+    Preview,
+    /// The `ShortenOptions::max_clicks` option.
+    MaxClicks,
+    /// The `ShortenOptions::private` option.
+    Private,
+    /// The `ShortenOptions::deep_link` option.
+    DeepLink,
+}
+
+/// Describes what happens to the options a provider is not able to honour.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum OptionsMode {
+    /// Fail with `ProviderError::UnsupportedOption`.
+    #[default]
+    Strict,
+    /// Silently drop the options.
+    Lenient,
+}
+
+/// Describes which URL schemes are accepted for shortening. The URLs with
+/// other schemes are rejected with `ProviderError::InvalidUrl` before any
+/// request is made.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub enum SchemePolicy {
+    /// Accept the schemes the provider is meant for (see
+    /// `Provider::schemes`), which are `http` and `https` for most of them.
+    #[default]
+    Provider,
+    /// Accept only the listed schemes, in lowercase.
+    Allow(Vec<String>),
+    /// Accept any scheme, forwarding the URL verbatim.
+    Any,
+}
+
+/// The app routing and attribution parameters of a deep link, for the
+/// mobile attribution providers (`Provider::Branch` and
+/// `Provider::OneLink`).
+///
+/// The fallbacks which are not set lead to the URL being shortened.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::options::{DeepLink, ShortenOptions};
+///
+/// let deep_link = DeepLink::new()
+///     .ios_url("https://apps.apple.com/app/id123456789")
+///     .android_package("com.example.app")
+///     .campaign("spring-sale")
+///     .data("product_id", "42");
+/// let options = ShortenOptions::new().deep_link(deep_link);
+/// assert_eq!(
+///     options.deep_link.unwrap().android_fallback().as_deref(),
+///     Some("https://play.google.com/store/apps/details?id=com.example.app")
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct DeepLink {
+    /// Where the iOS devices without the app are sent.
+    pub ios_url: Option<String>,
+    /// Where the Android devices without the app are sent.
+    pub android_url: Option<String>,
+    /// The package name of the Android app, whose Google Play page is the
+    /// Android fallback when `android_url` is not set.
+    pub android_package: Option<String>,
+    /// Where the desktop browsers are sent. OneLink sends them to the URL
+    /// shortened and does not support it.
+    pub desktop_url: Option<String>,
+    /// The name of the marketing campaign the link belongs to.
+    pub campaign: Option<String>,
+    /// The channel (the media source) the link is shared on.
+    pub channel: Option<String>,
+    /// The custom data passed to the app when the link is opened.
+    pub data: Vec<(String, String)>,
+}
+
+impl DeepLink {
+    /// Creates an empty set of deep link parameters.
+    pub fn new() -> DeepLink {
+        DeepLink::default()
+    }
+
+    /// Sets where the iOS devices without the app are sent.
+    pub fn ios_url<S: Into<String>>(mut self, url: S) -> DeepLink {
+        self.ios_url = Some(url.into());
+        self
+    }
+
+    /// Sets where the Android devices without the app are sent.
+    pub fn android_url<S: Into<String>>(mut self, url: S) -> DeepLink {
+        self.android_url = Some(url.into());
+        self
+    }
+
+    /// Sets the package name of the Android app.
+    pub fn android_package<S: Into<String>>(mut self, package: S) -> DeepLink {
+        self.android_package = Some(package.into());
+        self
+    }
+
+    /// Sets where the desktop browsers are sent.
+    pub fn desktop_url<S: Into<String>>(mut self, url: S) -> DeepLink {
+        self.desktop_url = Some(url.into());
+        self
+    }
+
+    /// Sets the name of the marketing campaign.
+    pub fn campaign<S: Into<String>>(mut self, campaign: S) -> DeepLink {
+        self.campaign = Some(campaign.into());
+        self
+    }
+
+    /// Sets the channel the link is shared on.
+    pub fn channel<S: Into<String>>(mut self, channel: S) -> DeepLink {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    /// Adds a custom key and value passed to the app.
+    pub fn data<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> DeepLink {
+        self.data.push((key.into(), value.into()));
+        self
+    }
+
+    /// Returns where the Android devices without the app are sent: the
+    /// `android_url`, or the Google Play page of the `android_package`.
+    pub fn android_fallback(&self) -> Option<String> {
+        match (&self.android_url, &self.android_package) {
+            (Some(url), _) => Some(url.clone()),
+            (None, Some(package)) => Some(format!(
+                "https://play.google.com/store/apps/details?id={}",
+                package
+            )),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Additional per-link options which a provider may honour when creating a
+/// short URL.
+///
+/// By default, requesting an option from a provider which can't honour it
+/// fails with `ProviderError::UnsupportedOption`, as the resulting short URL
+/// would not behave as expected. Use `OptionsMode::Lenient` to silently drop
+/// such options instead. The options a provider is able to honour are
+/// reported by `Provider::supports`.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::options::ShortenOptions;
+///
+/// let options = ShortenOptions::new()
+///     .title("Rust homepage")
+///     .tag("rust")
+///     .tag("docs");
+/// assert_eq!(options.tags.len(), 2);
+/// ```
+///
+/// An ephemeral link:
+///
+/// ```rust
+/// use std::time::Duration;
+/// use urlshortener::options::{Expiration, ShortenOptions};
+///
+/// let options = ShortenOptions::new().expires(Duration::from_secs(3600));
+/// assert_eq!(options.expires, Some(Expiration::In(Duration::from_secs(3600))));
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ShortenOptions {
+    /// A human-readable title of the link.
+    pub title: Option<String>,
+    /// Tags used to organize the link on the provider side.
+    pub tags: Vec<String>,
+    /// A custom slug of the short URL.
+    pub alias: Option<String>,
+    /// When the short URL stops working.
+    pub expires: Option<Expiration>,
+    /// A password which is asked before redirecting.
+    pub password: Option<String>,
+    /// A description of the link.
+    pub description: Option<String>,
+    /// A custom domain of the short URL.
+    pub domain: Option<String>,
+    /// Whether an existing short URL for the same target should be returned
+    /// instead of creating a new one.
+    pub reuse_existing: bool,
+    /// Whether the provider should collect the visit statistics of the link,
+    /// for the providers where it must be enabled explicitly.
+    pub log_stats: bool,
+    /// Whether the short URL should lead to a preview page showing the
+    /// destination instead of redirecting to it straight away.
+    ///
+    /// Only `UrlShortener` honours it, for is.gd and v.gd, by appending a
+    /// hyphen to the short URL. The requests and the responses can't carry
+    /// it, so `request_with_options` rejects it.
+    pub preview: bool,
+    /// The number of clicks after which the short URL stops working.
+    pub max_clicks: Option<u64>,
+    /// Whether the link should be unlisted, hidden from the public link
+    /// listings of the provider.
+    pub private: bool,
+    /// What happens to the options the provider is not able to honour.
+    pub mode: OptionsMode,
+    /// Which URL schemes are accepted for shortening.
+    pub schemes: SchemePolicy,
+    /// An identifier of the call, sent to the provider in the
+    /// `X-Correlation-ID` header to trace the call end to end. It is not
+    /// sent if it is not a valid header value.
+    pub correlation_id: Option<String>,
+    /// The app routing and attribution parameters of a deep link.
+    pub deep_link: Option<DeepLink>,
+}
+
+impl ShortenOptions {
+    /// Creates an empty set of options.
+    pub fn new() -> ShortenOptions {
+        ShortenOptions::default()
+    }
+
+    /// Sets the title of the link.
+    pub fn title<S: Into<String>>(mut self, title: S) -> ShortenOptions {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Adds a tag to the link.
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> ShortenOptions {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Adds several tags to the link.
+    pub fn tags<I, S>(mut self, tags: I) -> ShortenOptions
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the custom slug of the short URL.
+    pub fn alias<S: Into<String>>(mut self, alias: S) -> ShortenOptions {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Sets when the short URL stops working: either a `Duration` since the
+    /// creation or a `SystemTime`.
+    pub fn expires<E: Into<Expiration>>(mut self, expiration: E) -> ShortenOptions {
+        self.expires = Some(expiration.into());
+        self
+    }
+
+    /// Protects the short URL with a password.
+    pub fn password<S: Into<String>>(mut self, password: S) -> ShortenOptions {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Sets the description of the link.
+    pub fn description<S: Into<String>>(mut self, description: S) -> ShortenOptions {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the custom domain of the short URL.
+    pub fn domain<S: Into<String>>(mut self, domain: S) -> ShortenOptions {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets whether an existing short URL for the same target should be
+    /// returned instead of creating a new one.
+    pub fn reuse_existing(mut self, reuse: bool) -> ShortenOptions {
+        self.reuse_existing = reuse;
+        self
+    }
+
+    /// Sets whether the provider should collect the visit statistics of the
+    /// link.
+    pub fn log_stats(mut self, log_stats: bool) -> ShortenOptions {
+        self.log_stats = log_stats;
+        self
+    }
+
+    /// Sets whether the short URL should lead to a preview page showing the
+    /// destination instead of redirecting to it straight away, see
+    /// `ShortenOptions::preview`.
+    pub fn preview(mut self, preview: bool) -> ShortenOptions {
+        self.preview = preview;
+        self
+    }
+
+    /// Sets the number of clicks after which the short URL stops working.
+    pub fn max_clicks(mut self, max_clicks: u64) -> ShortenOptions {
+        self.max_clicks = Some(max_clicks);
+        self
+    }
+
+    /// Sets whether the link should be unlisted, hidden from the public link
+    /// listings of the provider.
+    pub fn private(mut self, private: bool) -> ShortenOptions {
+        self.private = private;
+        self
+    }
+
+    /// Sets what happens to the options the provider is not able to honour.
+    pub fn mode(mut self, mode: OptionsMode) -> ShortenOptions {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets which URL schemes are accepted for shortening.
+    pub fn schemes(mut self, schemes: SchemePolicy) -> ShortenOptions {
+        self.schemes = schemes;
+        self
+    }
+
+    /// Sets the identifier of the call, sent to the provider in the
+    /// `X-Correlation-ID` header.
+    pub fn correlation_id<S: Into<String>>(mut self, id: S) -> ShortenOptions {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// Sets the app routing and attribution parameters of a deep link.
+    pub fn deep_link(mut self, deep_link: DeepLink) -> ShortenOptions {
+        self.deep_link = Some(deep_link);
+        self
+    }
+
+    /// Returns the kinds of the options which are set.
+    pub fn kinds(&self) -> Vec<OptionKind> {
+        let set = [
+            (OptionKind::Title, self.title.is_some()),
+            (OptionKind::Tags, !self.tags.is_empty()),
+            (OptionKind::Alias, self.alias.is_some()),
+            (OptionKind::Expiration, self.expires.is_some()),
+            (OptionKind::Password, self.password.is_some()),
+            (OptionKind::Description, self.description.is_some()),
+            (OptionKind::Domain, self.domain.is_some()),
+            (OptionKind::ReuseExisting, self.reuse_existing),
+            (OptionKind::LogStats, self.log_stats),
+            (OptionKind::Preview, self.preview),
+            (OptionKind::MaxClicks, self.max_clicks.is_some()),
+            (OptionKind::Private, self.private),
+            (OptionKind::DeepLink, self.deep_link.is_some()),
+        ];
+        set.iter()
+            .filter(|(_, is_set)| *is_set)
+            .map(|(kind, _)| *kind)
+            .collect()
+    }
+
+    /// Unsets the option of the given kind.
+    pub fn clear(&mut self, kind: OptionKind) {
+        match kind {
+            OptionKind::Title => self.title = None,
+            OptionKind::Tags => self.tags.clear(),
+            OptionKind::Alias => self.alias = None,
+            OptionKind::Expiration => self.expires = None,
+            OptionKind::Password => self.password = None,
+            OptionKind::Description => self.description = None,
+            OptionKind::Domain => self.domain = None,
+            OptionKind::ReuseExisting => self.reuse_existing = false,
+            OptionKind::LogStats => self.log_stats = false,
+            OptionKind::Preview => self.preview = false,
+            OptionKind::MaxClicks => self.max_clicks = None,
+            OptionKind::Private => self.private = false,
+            OptionKind::DeepLink => self.deep_link = None,
+        }
+    }
+}
@@ -1,20 +1,188 @@
 //! Library service providers implementation.
 
+use crate::credentials::{CredentialProvider, Secret};
+use crate::encode;
+use crate::links::{Link, LinkPage};
+use crate::local::{self, LinkStore};
+use crate::options::{OptionKind, OptionsMode, SchemePolicy, ShortenOptions};
 use crate::request as req;
-use reqwest::header::HeaderMap;
-use url::form_urlencoded;
+use crate::stats::{LinkStats, StatsPeriod};
+use http::header::{self, HeaderMap, HeaderValue};
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// A user agent for faking weird services.
 const FAKE_USER_AGENT: &str =
     "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:58.0) Gecko/20100101 Firefox/58.0";
 
 /// Describes the provider error.
+///
+/// New errors may be added in the minor releases.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
 pub enum ProviderError {
     /// Means there was a connection error. Usually when making a request.
     Connection,
     /// Means we were not able to deserialize the answer.
     Deserialize,
+    /// Means the provider does not support the requested operation.
+    Unsupported,
+    /// Means the provider rejected the URL to shorten as invalid.
+    InvalidUrl,
+    /// Means the requested custom alias is already taken or invalid.
+    AliasTaken,
+    /// Means the provider rate limit has been exceeded.
+    RateLimited,
+    /// Means the provider is temporarily unavailable.
+    Unavailable,
+    /// Means the provider service has been shut down.
+    Discontinued,
+    /// Means the provider rejected the credentials.
+    Unauthorized,
+    /// Means the provider is not able to honour the link option.
+    UnsupportedOption(OptionKind),
+    /// Means the provider responded with one of its documented error codes.
+    /// See `ProviderError::kind` for the provider-independent error.
+    Provider(ProviderCode),
+    /// Means the URL to shorten leads to a missing or failing page, see
+    /// `UrlShortener::with_destination_check`.
+    DestinationUnreachable,
+}
+
+impl ProviderError {
+    /// Returns the provider-independent error: the one the provider error
+    /// code corresponds to for `ProviderError::Provider`, the error itself
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::{CuttlyError, ProviderCode, ProviderError};
+    ///
+    /// let error = ProviderError::Provider(ProviderCode::Cuttly(CuttlyError::AliasTaken));
+    /// assert_eq!(error.kind(), ProviderError::AliasTaken);
+    /// ```
+    pub fn kind(self) -> ProviderError {
+        match self {
+            ProviderError::Provider(code) => code.kind(),
+            error => error,
+        }
+    }
+}
+
+/// The documented error codes of a provider.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ProviderCode {
+    /// An error of the bit.ly API.
+    BitLy(BitLyError),
+    /// A status of the cutt.ly API.
+    Cuttly(CuttlyError),
+    /// An error code of the is.gd and v.gd API.
+    IsGd(IsGdError),
+    /// An error of the kutt.it API.
+    Kutt(KuttError),
+}
+
+impl ProviderCode {
+    /// Returns the provider-independent error the code corresponds to.
+    pub fn kind(self) -> ProviderError {
+        match self {
+            ProviderCode::BitLy(error) => match error {
+                BitLyError::AlreadyABitlyLink | BitLyError::InvalidLongUrl => {
+                    ProviderError::InvalidUrl
+                }
+                BitLyError::InvalidArgument => ProviderError::Deserialize,
+                BitLyError::LimitExceeded => ProviderError::RateLimited,
+                BitLyError::Forbidden => ProviderError::Unauthorized,
+            },
+            ProviderCode::Cuttly(error) => match error {
+                CuttlyError::AlreadyShortened
+                | CuttlyError::NotALink
+                | CuttlyError::InvalidCharacters
+                | CuttlyError::BlockedDomain => ProviderError::InvalidUrl,
+                CuttlyError::AliasTaken => ProviderError::AliasTaken,
+                CuttlyError::InvalidApiKey => ProviderError::Unauthorized,
+                CuttlyError::LimitReached => ProviderError::RateLimited,
+            },
+            ProviderCode::IsGd(error) => match error {
+                IsGdError::InvalidUrl => ProviderError::InvalidUrl,
+                IsGdError::InvalidAlias => ProviderError::AliasTaken,
+                IsGdError::RateLimited => ProviderError::RateLimited,
+                IsGdError::Other => ProviderError::Unavailable,
+            },
+            ProviderCode::Kutt(error) => match error {
+                KuttError::InvalidUrl => ProviderError::InvalidUrl,
+                KuttError::AliasTaken => ProviderError::AliasTaken,
+                KuttError::InvalidApiKey => ProviderError::Unauthorized,
+                KuttError::LimitReached => ProviderError::RateLimited,
+            },
+        }
+    }
+}
+
+/// The errors reported by the bit.ly API in the `message` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum BitLyError {
+    /// `ALREADY_A_BITLY_LINK`: the URL is a bit.ly link already.
+    AlreadyABitlyLink,
+    /// `INVALID_ARG_LONG_URL`: the URL is not valid.
+    InvalidLongUrl,
+    /// Any other `INVALID_ARG_*` error: an argument of the request is not
+    /// valid.
+    InvalidArgument,
+    /// Any `*_LIMIT_EXCEEDED` error: a limit of the account is exceeded.
+    LimitExceeded,
+    /// `FORBIDDEN`: the token is not allowed to create links.
+    Forbidden,
+}
+
+/// The statuses of the cutt.ly API, other than the successful `7`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum CuttlyError {
+    /// `1`: the URL is shortened already.
+    AlreadyShortened,
+    /// `2`: the URL is not a link.
+    NotALink,
+    /// `3`: the alias is taken.
+    AliasTaken,
+    /// `4`: the api key is invalid.
+    InvalidApiKey,
+    /// `5`: the URL contains invalid characters.
+    InvalidCharacters,
+    /// `6`: the domain of the URL is blocked.
+    BlockedDomain,
+    /// `8`: the monthly limit of links is reached.
+    LimitReached,
+}
+
+/// The error codes of the is.gd and v.gd API.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum IsGdError {
+    /// `1`: the URL is not valid or is blacklisted.
+    InvalidUrl,
+    /// `2`: the alias is taken or is not valid.
+    InvalidAlias,
+    /// `3`: the rate limit is exceeded.
+    RateLimited,
+    /// `4`: any other error, for example the service is down.
+    Other,
+}
+
+/// The errors reported by the kutt.it API in the `error` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum KuttError {
+    /// The URL is not valid or is not allowed.
+    InvalidUrl,
+    /// The custom URL is already in use.
+    AliasTaken,
+    /// The api key is invalid.
+    InvalidApiKey,
+    /// The daily limit of links is reached.
+    LimitReached,
 }
 
 impl std::fmt::Display for ProviderError {
@@ -28,6 +196,22 @@ impl std::fmt::Display for ProviderError {
                 f,
                 "Couldn't deserialize the shortened URL from the response."
             ),
+            Self::Unsupported => {
+                write!(f, "The provider does not support the requested operation.")
+            }
+            Self::InvalidUrl => write!(f, "The provider rejected the URL as invalid."),
+            Self::AliasTaken => write!(f, "The requested alias is already taken or invalid."),
+            Self::RateLimited => write!(f, "The provider rate limit has been exceeded."),
+            Self::Unavailable => write!(f, "The provider is temporarily unavailable."),
+            Self::Discontinued => write!(f, "The provider service has been shut down."),
+            Self::Unauthorized => write!(f, "The provider rejected the credentials."),
+            Self::UnsupportedOption(kind) => {
+                write!(f, "The provider does not support the {:?} option.", kind)
+            }
+            Self::Provider(code) => write!(f, "The provider responded with {:?}.", code),
+            Self::DestinationUnreachable => {
+                write!(f, "The URL to shorten leads to a missing or failing page.")
+            }
         }
     }
 }
@@ -54,6 +238,14 @@ pub const PROVIDERS: &[Provider] = &[
     Provider::UrlShortenerIo,
     Provider::HmmRs,
     Provider::BitUrl,
+    Provider::ClckRu,
+    Provider::SpooMe,
+    Provider::CleanUri,
+    Provider::GgGg,
+    Provider::VHt,
+    Provider::N9Cl { api_key: None },
+    Provider::LnnkIn,
+    Provider::VurlCom,
     // The following list are items that have long response sometimes:
     Provider::TnyIm,
     // The following list are items that are discouraged from use:
@@ -78,15 +270,118 @@ pub const PROVIDERS: &[Provider] = &[
     Provider::NowLinks,
 ];
 
+/// Returns the text of the first `tag` element of the xml document, or an
+/// empty string if there is no such element.
+fn xml_tag(res: &str, tag: &str) -> Option<String> {
+    res.split(&format!("<{}>", tag))
+        .nth(1)
+        .unwrap_or("")
+        .split(&format!("</{}>", tag))
+        .next()
+        .map(String::from)
+}
+
+/// Returns the string value of the first `tag` field of the json document,
+/// with the escaping backslashes removed and the prefix prepended.
+fn json_tag(res: &str, tag: &str, prefix: &str) -> Option<String> {
+    res.split(&format!("\"{}\"", tag))
+        .nth(1)?
+        .split(',')
+        .next()?
+        .split('"')
+        .nth(1)
+        .map(|v| format!("{}{}", prefix, v.replace('\\', "")))
+}
+
+/// The domains of the known URL shortener services, the built-in providers
+/// included, in alphabetical order.
+const KNOWN_DOMAINS: &[&str] = &[
+    "9qr.de",
+    "abv8.me",
+    "adf.ly",
+    "adfoc.us",
+    "amzn.to",
+    "app.link",
+    "bam.bz",
+    "bc.vc",
+    "bit.do",
+    "bit.ly",
+    "bitly.com",
+    "biturl.top",
+    "bl.ink",
+    "bmeo.org",
+    "branch.io",
+    "buff.ly",
+    "clck.ru",
+    "cleanuri.com",
+    "cli.gs",
+    "cutt.ly",
+    "cutt.us",
+    "db.tt",
+    "dlvr.it",
+    "fb.me",
+    "fifo.cc",
+    "gg.gg",
+    "goo.gl",
+    "goo.su",
+    "hec.su",
+    "hmm.rs",
+    "ift.tt",
+    "is.gd",
+    "j.mp",
+    "kutt.it",
+    "lihi.cc",
+    "lnkd.in",
+    "lnnk.in",
+    "mcaf.ee",
+    "mgnet.me",
+    "n9.cl",
+    "nowlinks.net",
+    "onelink.me",
+    "ouo.io",
+    "ow.ly",
+    "phx.co.in",
+    "po.st",
+    "psbe.co",
+    "pse.is",
+    "qr.ae",
+    "rb.gy",
+    "rebrand.ly",
+    "rlu.ru",
+    "s.coop",
+    "s.id",
+    "sh.st",
+    "shiny.link",
+    "short.gy",
+    "shorte.st",
+    "shorturl.at",
+    "shrtco.de",
+    "sirbz.com",
+    "snip.ly",
+    "spoo.me",
+    "t.co",
+    "t.ly",
+    "t2m.io",
+    "tiny.cc",
+    "tiny.ph",
+    "tinyurl.com",
+    "tny.im",
+    "tr.im",
+    "trib.al",
+    "u.to",
+    "url-shortener.io",
+    "v.gd",
+    "v.ht",
+    "vurl.com",
+    "wp.me",
+    "x.co",
+    "youtu.be",
+];
+
 macro_rules! parse_xml_tag {
     ($fname:ident, $tag:expr) => {
         fn $fname(res: &str) -> Option<String> {
-            res.split(&format!("<{}>", $tag))
-                .nth(1)
-                .unwrap_or("")
-                .split(&format!("</{}>", $tag))
-                .next()
-                .map(String::from)
+            xml_tag(res, $tag)
         }
     };
 }
@@ -94,16 +389,7 @@ macro_rules! parse_xml_tag {
 macro_rules! parse_json_tag {
     ($fname:ident, $tag:expr, $prefix:expr) => {
         fn $fname(res: &str) -> Option<String> {
-            res.to_owned()
-                .split(&format!("\"{}\"", $tag))
-                .nth(1)
-                .unwrap_or("")
-                .split(",")
-                .next()
-                .unwrap_or("")
-                .split("\"")
-                .nth(1)
-                .map(|v| format!("{}{}", $prefix, v.replace("\\", "")))
+            json_tag(res, $tag, $prefix)
         }
     };
 }
@@ -119,7 +405,7 @@ macro_rules! parse_noop {
 macro_rules! request {
     ($name:ident, $method:expr, $req_url:expr) => {
         fn $name(url: &str) -> req::Request {
-            let url = form_urlencoded::byte_serialize(url.as_bytes()).collect::<String>();
+            let url = encode::byte_serialize(url.as_bytes());
             req::Request {
                 url: format!($req_url, url),
                 body: None,
@@ -159,7 +445,13 @@ macro_rules! request {
 }
 
 /// Used to specify which provider to use to generate a short URL.
-#[derive(Clone, Debug)]
+///
+/// The credentials are masked in the `Debug` output, use `Provider::reveal`
+/// to show them.
+///
+/// New providers may be added in the minor releases.
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum Provider {
     /// <http://abv8.me> provider
     ///
@@ -169,6 +461,20 @@ pub enum Provider {
     /// * You may not shorten more than 60 unique URLs within a 15-minute
     ///   period.
     Abv8,
+    /// <https://adf.ly> monetized provider
+    ///
+    /// Notes:
+    ///
+    /// * The visitors are shown adverts before being redirected, so the
+    ///   provider is not in the `PROVIDERS` list.
+    AdfLy {
+        /// An api key string which you may obtain on the provider web service page.
+        api_key: String,
+        /// The user id which you may obtain on the provider web service page.
+        user_id: String,
+        /// The type of the adverts shown to the visitors.
+        advert_type: AdfLyAdvert,
+    },
     /// <https://bam.bz> provider
     BamBz,
     /// <https://bit.ly> provider
@@ -178,11 +484,69 @@ pub enum Provider {
     },
     /// <https://biturl.top> provider
     BitUrl,
+    /// <https://bl.ink> provider
+    Blink {
+        /// An api token string which you may obtain on the provider web service page.
+        api_token: String,
+        /// The identifier of the domain to create links on.
+        domain_id: u64,
+    },
     /// <http://bmeo.org> provider
     Bmeo,
+    /// <https://branch.io> deep link provider
+    ///
+    /// Notes:
+    ///
+    /// * The shortened URL is used as the web fallback of the created deep
+    ///   link, the app routing is configured on the Branch dashboard.
+    Branch {
+        /// The branch key of the app which you may obtain on the provider web service page.
+        key: String,
+    },
+    /// <https://github.com/SinTan1729/chhoto-url> self hosted provider
+    ChhotoUrl {
+        /// An api key string which is configured on the instance.
+        api_key: String,
+        /// The instance host, for example, '<https://chhoto.example.com>'
+        host: String,
+    },
+    /// <https://cleanuri.com> provider
+    CleanUri,
+    /// <https://clck.ru> provider of Yandex
+    ClckRu,
+    /// <https://cutt.ly> provider
+    Cuttly {
+        /// An api key string which you may obtain on the provider web service page.
+        api_key: String,
+    },
+    /// A fake provider deriving the short URLs from the URLs
+    /// deterministically, for the reproducible tests and the demos
+    ///
+    /// Notes:
+    ///
+    /// * No request is sent and nothing is stored: the same URL always gets
+    ///   the same short URL, which does not lead anywhere. See
+    ///   `shorten_locally` for the use without the client.
+    /// * The `{hash}` placeholder of the pattern is replaced by the base62
+    ///   encoded hash of the URL, or by the alias if one is given. The slug
+    ///   is appended to the pattern if it has no placeholder.
+    Fake {
+        /// The pattern of the short URLs, for example
+        /// `https://fake.link/{hash}`.
+        pattern: String,
+    },
     /// <http://fifo.cc> provider
     FifoCc,
+    /// <http://gg.gg> provider
+    GgGg,
     /// <https://goo.gl> provider of Google
+    ///
+    /// Notes:
+    ///
+    /// * The service API was shut down in 2019, shortening always fails with
+    ///   `ProviderError::Discontinued`. Consider using `BitLy`, `Kutt` or
+    ///   `TinyUrlApi` instead.
+    #[deprecated(note = "The goo.gl API has been shut down, use another provider instead.")]
     GooGl {
         /// An api key string which you may obtain on the provider web service page.
         api_key: String,
@@ -204,8 +568,69 @@ pub enum Provider {
     HmmRs,
     /// <https://is.gd> provider
     IsGd,
+    /// <https://lihi.cc> provider
+    LihiCc {
+        /// An api token string which you may obtain on the provider web service page.
+        token: String,
+        /// The additional destinations for A/B split testing. The visitors
+        /// are split evenly between the shortened URL and these ones.
+        split_urls: Vec<String>,
+    },
+    /// <https://lnnk.in> provider
+    LnnkIn,
+    /// An embedded provider storing the links in a local store, for the
+    /// development and the offline use
+    ///
+    /// Notes:
+    ///
+    /// * No request is sent: the client stores the links and expands the
+    ///   short URLs itself, see `shorten_locally` and `expand_locally` for
+    ///   the use without the client.
+    /// * The slugs are the base62 encoded link numbers, see `local::base62`.
+    Local {
+        /// The URL the short URLs are created under, for example
+        /// `http://localhost:8080/s`.
+        base_url: String,
+        /// The storage of the links.
+        store: Arc<dyn LinkStore>,
+    },
+    /// <http://mgnet.me> provider
+    ///
+    /// Notes:
+    ///
+    /// * Only shortens `magnet:` URIs.
+    MgnetMe,
+    /// <https://n9.cl> provider
+    N9Cl {
+        /// An optional api key string which you may obtain on the provider
+        /// web service page, links are created anonymously without it.
+        api_key: Option<String>,
+    },
     /// <http://nowlinks.net> provider
     NowLinks,
+    /// <https://www.appsflyer.com> OneLink attribution link provider
+    ///
+    /// Notes:
+    ///
+    /// * The shortened URL is used as the web fallback of the created
+    ///   attribution link, the media source and campaign parameters are
+    ///   taken from the OneLink template.
+    OneLink {
+        /// The OneLink template id which you may find on the provider web service page.
+        template_id: String,
+        /// An api token string which you may obtain on the provider web service page.
+        api_token: String,
+    },
+    /// <https://ouo.io> monetized provider
+    ///
+    /// Notes:
+    ///
+    /// * The visitors are shown adverts before being redirected, so the
+    ///   provider is not in the `PROVIDERS` list.
+    OuoIo {
+        /// An api key string which you may obtain on the provider web service page.
+        api_key: String,
+    },
     /// <http://phx.co.in> provider
     ///
     /// Notes:
@@ -216,8 +641,37 @@ pub enum Provider {
     PhxCoIn,
     /// <http://psbe.co> provider
     PsbeCo,
+    /// <https://pse.is> provider of PicSee
+    PseIs {
+        /// An access token string which you may obtain on the provider web service page.
+        token: String,
+    },
+    /// <https://snip.ly> provider
+    ///
+    /// Notes:
+    ///
+    /// * The target page is shown with a call to action overlay, so the
+    ///   links are meant for marketing campaigns.
+    SnipLy {
+        /// An OAuth access token which you may obtain on the provider web service page.
+        token: String,
+        /// The id of the call to action created on the provider web service
+        /// page, the default one of the account is used if not set.
+        cta: Option<String>,
+        /// The campaign to group the created links in.
+        campaign: Option<String>,
+    },
+    /// <https://spoo.me> provider
+    SpooMe,
     /// <http://s.coop> provider
     SCoop,
+    /// <https://rebrandly.com> provider
+    Rebrandly {
+        /// An api key string which you may obtain on the provider web service page.
+        api_key: String,
+        /// The branded domain to use, defaults to 'rebrand.ly'
+        domain: Option<String>,
+    },
     /// <http://rlu.ru> provider
     ///
     /// Notes:
@@ -227,19 +681,73 @@ pub enum Provider {
     ///   the technical support know. Otherwise your IP can be blocked
     ///   unexpectedly. Prior added URLs can be deleted.
     Rlu,
+    /// <https://short.io> provider
+    ShortIo {
+        /// A secret api key string which you may obtain on the provider web service page.
+        api_key: String,
+        /// The domain registered on the service to create links on.
+        domain: String,
+    },
+    /// <https://shorte.st> monetized provider
+    ///
+    /// Notes:
+    ///
+    /// * The visitors are shown adverts before being redirected, so the
+    ///   provider is not in the `PROVIDERS` list.
+    ShorteSt {
+        /// An api token string which you may obtain on the provider web service page.
+        api_token: String,
+    },
+    /// <https://shrtco.de> provider
+    ///
+    /// Notes:
+    ///
+    /// * Every link is also available on the `9qr.de` and `shiny.link`
+    ///   domains, which may be picked via `ShortenOptions::domain`.
+    /// * The API has been shut down, so the provider is not in the
+    ///   `PROVIDERS` list.
+    ShrtcoDe,
+    /// <https://s.id> provider
+    SId {
+        /// An api client id string which you may obtain on the provider web service page.
+        client_id: String,
+        /// An api secret key string which you may obtain on the provider web service page.
+        api_key: String,
+    },
     /// <http://sirbz.com> provider
     ///
     /// Notes:
     ///
     /// * By default, you are limited to 250 requests per 15 minutes.
     SirBz,
+    /// <https://t2m.io> provider
+    ///
+    /// Notes:
+    ///
+    /// * A QR code is generated for every link, use `parse_t2m_link` to
+    ///   obtain its URL.
+    T2m {
+        /// An api key string which you may obtain on the provider web service page.
+        api_key: String,
+    },
     /// <http://tinyurl.com> provider
     ///
     /// Notes:
     ///
-    /// * This service does not provide any API.
-    /// * The implementation result depends on the service result web page.
+    /// * This implementation does not use the service API.
+    /// * The implementation result depends on the service result web page,
+    ///   consider using `TinyUrlApi` instead.
     TinyUrl,
+    /// <https://tinyurl.com> provider using the official API
+    ///
+    /// Notes:
+    ///
+    /// * The `ShortenOptions::domain` may be used to pick another domain of
+    ///   the service, for example, `tiny.one`.
+    TinyUrlApi {
+        /// An api token string which you may obtain on the provider web service page.
+        token: String,
+    },
     /// <http://tiny.ph> provider
     TinyPh,
     /// <http://tny.im> provider
@@ -248,162 +756,1977 @@ pub enum Provider {
     UrlShortenerIo,
     /// <https://v.gd> provider
     VGd,
+    /// <https://v.ht> provider
+    VHt,
+    /// <https://vurl.com> provider
+    VurlCom,
+    /// <https://yourls.org> self hosted provider
+    Yourls {
+        /// The base URL of the instance, the API is expected at
+        /// `{base_url}/yourls-api.php`.
+        base_url: String,
+        /// The authentication used for the API requests.
+        auth: YourlsAuth,
+    },
 }
 
-impl Provider {
-    /// Converts the Provider variant into its domain name equivilant
-    pub fn to_name(&self) -> &str {
-        match *self {
-            Provider::Abv8 => "abv8.me",
-            Provider::BamBz => "bam.bz",
-            Provider::BitLy { .. } => "bitly.com",
-            Provider::BitUrl => "biturl.top",
-            Provider::Bmeo => "bmeo.org",
-            Provider::FifoCc => "fifo.cc",
-            Provider::GooGl { .. } => "goo.gl",
-            Provider::HmmRs => "hmm.rs",
-            Provider::HecSu => "hec.su",
-            Provider::IsGd => "is.gd",
-            Provider::Kutt { ref host, .. } => host
-                .as_ref()
-                .map(|h| h.rsplit("//").next().unwrap())
-                .unwrap_or("kutt.it"),
-            Provider::NowLinks => "nowlinks.net",
-            Provider::PhxCoIn => "phx.co.in",
-            Provider::PsbeCo => "psbe.co",
-            Provider::SCoop => "s.coop",
-            Provider::SirBz => "sirbz.com",
-            Provider::Rlu => "rlu.ru",
-            Provider::TinyUrl => "tinyurl.com",
-            Provider::TinyPh => "tiny.ph",
-            Provider::TnyIm => "tny.im",
-            Provider::UrlShortenerIo => "url-shortener.io",
-            Provider::VGd => "v.gd",
+/// The type of the adverts shown by adf.ly before redirecting the visitors.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AdfLyAdvert {
+    /// A full page advert which the visitors may skip after a few seconds.
+    Interstitial,
+    /// A banner shown on top of the target page.
+    Banner,
+}
+
+impl AdfLyAdvert {
+    fn as_str(self) -> &'static str {
+        match self {
+            AdfLyAdvert::Interstitial => "int",
+            AdfLyAdvert::Banner => "banner",
         }
     }
 }
 
-parse_noop!(abv8_parse);
-request!(abv8_req, req::Method::Get, "http://abv8.me/?url={}");
+/// The number of requests a provider accepts within a time window.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Quota {
+    /// The number of requests allowed within the window.
+    pub requests: u32,
+    /// The length of the window.
+    pub window: Duration,
+}
 
-parse_json_tag!(bambz_parse, "url", "");
-request!(
-    bambz_req,
-    req::Method::Post,
-    "https://bam.bz/api/short",
-    "target={}",
-    req::ContentType::FormUrlEncoded
-);
+impl Quota {
+    /// Creates new `Quota` of `requests` per `window`.
+    pub fn new(requests: u32, window: Duration) -> Quota {
+        Quota { requests, window }
+    }
+}
 
-parse_noop!(bitly_parse);
-fn bitly_req(url: &str, key: &str) -> req::Request {
-    let encoded_url = form_urlencoded::byte_serialize(url.as_bytes()).collect::<String>();
-    let address = format!(
-        "https://api-ssl.bitly.com/v3/shorten?access_token={}&longUrl={}&format=txt",
-        key, encoded_url
-    );
+/// How quickly a provider usually responds, which determines how long the
+/// client waits for it when other providers may be tried instead.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum TimeoutTier {
+    /// The provider responds quickly: the client waits half of its timeout.
+    Fast,
+    /// The client waits its timeout.
+    #[default]
+    Normal,
+    /// The provider sometimes responds slowly: the client waits twice its
+    /// timeout, up to `Duration::MAX`.
+    Slow,
+}
 
-    req::Request {
-        url: address,
-        body: None,
-        content_type: None,
-        user_agent: None,
-        headers: None,
-        method: req::Method::Get,
+impl TimeoutTier {
+    /// Returns the time to wait for the provider, given the client timeout.
+    pub fn scale(self, timeout: Duration) -> Duration {
+        match self {
+            TimeoutTier::Fast => timeout / 2,
+            TimeoutTier::Normal => timeout,
+            TimeoutTier::Slow => timeout.saturating_mul(2),
+        }
     }
 }
 
-parse_json_tag!(bmeo_parse, "short", "");
-request!(bmeo_req, req::Method::Get, "http://bmeo.org/api.php?url={}");
+/// The short URLs created by shrtco.de, which are available on all of its
+/// domains.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ShrtcoDeLinks {
+    /// The short URL on the `shrtco.de` domain.
+    pub shrtco_de: String,
+    /// The short URL on the `9qr.de` domain.
+    pub nine_qr_de: String,
+    /// The short URL on the `shiny.link` domain.
+    pub shiny_link: String,
+}
 
-parse_json_tag!(fifocc_parse, "shortner", "http://fifo.cc/");
-request!(
-    fifocc_req,
-    req::Method::Get,
-    "https://fifo.cc/api/v2?url={}"
-);
+/// The short link created by t2m.io.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct T2mLink {
+    /// The short URL.
+    pub short_url: String,
+    /// The URL of the QR code image leading to the short URL.
+    pub qr_code: Option<String>,
+}
 
-parse_json_tag!(googl_parse, "id", "");
-fn googl_req(url: &str, key: &str) -> req::Request {
-    req::Request {
-        url: format!("https://www.googleapis.com/urlshortener/v1/url?key={}", key),
-        body: Some(format!(r#"{{"longUrl": "{}"}}"#, url)),
-        content_type: Some(req::ContentType::Json),
-        user_agent: None,
-        headers: None,
-        method: req::Method::Post,
-    }
+/// Describes the authentication used for the YOURLS API requests.
+#[derive(Clone)]
+pub enum YourlsAuth {
+    /// The instance is public and does not require authentication.
+    Public,
+    /// The secret signature token, which you may find on the tools page of
+    /// the instance admin area.
+    Signature(String),
+    /// The user credentials.
+    Password {
+        /// The user name.
+        username: String,
+        /// The user password.
+        password: String,
+    },
 }
 
-parse_json_tag!(hmmrs_parse, "shortUrl", "");
-fn hmmrs_req(url: &str) -> req::Request {
-    req::Request {
-        url: "http:/hmm.rs/x/shorten".to_owned(),
-        body: Some(format!(r#"{{"url": "{}"}}"#, url)),
-        content_type: Some(req::ContentType::Json),
-        user_agent: Some(req::UserAgent(FAKE_USER_AGENT.to_owned())),
-        headers: None,
-        method: req::Method::Post,
+/// A configuration value of a provider, which the providers are compared and
+/// hashed by.
+enum Setting<'a> {
+    Value(Option<&'a str>),
+    Number(u64),
+    /// A credential, which is compared in constant time and not hashed.
+    Secret(Option<&'a str>),
+}
+
+impl<'a> PartialEq for Setting<'a> {
+    fn eq(&self, other: &Setting) -> bool {
+        match (self, other) {
+            (Setting::Value(a), Setting::Value(b)) => a == b,
+            (Setting::Number(a), Setting::Number(b)) => a == b,
+            (Setting::Secret(Some(a)), Setting::Secret(Some(b))) => constant_time_eq(a, b),
+            (Setting::Secret(None), Setting::Secret(None)) => true,
+            _ => false,
+        }
     }
 }
 
-parse_xml_tag!(hecsu_parse, "short");
-request!(
-    hecsu_req,
-    req::Method::Get,
-    "https://hec.su/api?url={}&method=xml"
-);
+impl<'a> Hash for Setting<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Setting::Value(value) => value.hash(state),
+            Setting::Number(number) => number.hash(state),
+            Setting::Secret(secret) => secret.is_some().hash(state),
+        }
+    }
+}
 
-parse_noop!(isgd_parse);
-request!(
-    isgd_req,
-    req::Method::Get,
-    "https://is.gd/create.php?format=simple&url={}"
-);
+/// Compares the strings in a time which depends on their lengths only.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
 
-parse_json_tag!(kutt_parse, "shortUrl", "");
-fn kutt_req(url: &str, api_key: &str, host: Option<&str>) -> req::Request {
-    let mut headers = HeaderMap::new();
-    headers.insert("X-API-Key", api_key.parse().unwrap());
+impl Provider {
+    /// Returns the configuration values of the provider.
+    #[allow(deprecated)]
+    fn settings(&self) -> Vec<Setting<'_>> {
+        match *self {
+            Provider::AdfLy {
+                ref api_key,
+                ref user_id,
+                advert_type,
+            } => vec![
+                Setting::Secret(Some(api_key)),
+                Setting::Value(Some(user_id)),
+                Setting::Value(Some(advert_type.as_str())),
+            ],
+            Provider::BitLy { ref token } => vec![Setting::Secret(Some(token))],
+            Provider::Blink {
+                ref api_token,
+                domain_id,
+            } => vec![Setting::Secret(Some(api_token)), Setting::Number(domain_id)],
+            Provider::Branch { ref key } => vec![Setting::Secret(Some(key))],
+            Provider::ChhotoUrl {
+                ref api_key,
+                ref host,
+            } => vec![Setting::Secret(Some(api_key)), Setting::Value(Some(host))],
+            Provider::Cuttly { ref api_key } => vec![Setting::Secret(Some(api_key))],
+            Provider::Fake { ref pattern } => vec![Setting::Value(Some(pattern))],
+            Provider::GooGl { ref api_key } => vec![Setting::Secret(Some(api_key))],
+            Provider::Kutt {
+                ref api_key,
+                ref host,
+            } => vec![
+                Setting::Secret(Some(api_key)),
+                Setting::Value(host.as_deref()),
+            ],
+            Provider::Local {
+                ref base_url,
+                ref store,
+            } => vec![
+                Setting::Value(Some(base_url)),
+                Setting::Number(Arc::as_ptr(store) as *const () as usize as u64),
+            ],
+            Provider::N9Cl { ref api_key } => vec![Setting::Secret(api_key.as_deref())],
+            Provider::OneLink {
+                ref template_id,
+                ref api_token,
+            } => vec![
+                Setting::Value(Some(template_id)),
+                Setting::Secret(Some(api_token)),
+            ],
+            Provider::OuoIo { ref api_key } => vec![Setting::Secret(Some(api_key))],
+            Provider::PseIs { ref token } => vec![Setting::Secret(Some(token))],
+            Provider::SnipLy {
+                ref token,
+                ref cta,
+                ref campaign,
+            } => vec![
+                Setting::Secret(Some(token)),
+                Setting::Value(cta.as_deref()),
+                Setting::Value(campaign.as_deref()),
+            ],
+            Provider::Rebrandly {
+                ref api_key,
+                ref domain,
+            } => vec![
+                Setting::Secret(Some(api_key)),
+                Setting::Value(domain.as_deref()),
+            ],
+            Provider::ShortIo {
+                ref api_key,
+                ref domain,
+            } => vec![Setting::Secret(Some(api_key)), Setting::Value(Some(domain))],
+            Provider::ShorteSt { ref api_token } => vec![Setting::Secret(Some(api_token))],
+            Provider::SId {
+                ref client_id,
+                ref api_key,
+            } => vec![
+                Setting::Value(Some(client_id)),
+                Setting::Secret(Some(api_key)),
+            ],
+            Provider::T2m { ref api_key } => vec![Setting::Secret(Some(api_key))],
+            Provider::TinyUrlApi { ref token } => vec![Setting::Secret(Some(token))],
+            Provider::LihiCc {
+                ref token,
+                ref split_urls,
+            } => {
+                let mut settings = vec![
+                    Setting::Secret(Some(token)),
+                    Setting::Number(split_urls.len() as u64),
+                ];
+                settings.extend(split_urls.iter().map(|url| Setting::Value(Some(url))));
+                settings
+            }
+            Provider::Yourls {
+                ref base_url,
+                ref auth,
+            } => {
+                let mut settings = vec![Setting::Value(Some(base_url))];
+                match *auth {
+                    YourlsAuth::Public => settings.push(Setting::Value(Some("public"))),
+                    YourlsAuth::Signature(ref signature) => {
+                        settings.push(Setting::Value(Some("signature")));
+                        settings.push(Setting::Secret(Some(signature)));
+                    }
+                    YourlsAuth::Password {
+                        ref username,
+                        ref password,
+                    } => {
+                        settings.push(Setting::Value(Some("password")));
+                        settings.push(Setting::Value(Some(username)));
+                        settings.push(Setting::Secret(Some(password)));
+                    }
+                }
+                settings
+            }
+            _ => Vec::new(),
+        }
+    }
+}
 
-    req::Request {
-        url: format!("{}/api/url/submit", host.unwrap_or("https://kutt.it")),
-        body: Some(format!(r#"{{"target": "{}"}}"#, url)),
-        content_type: Some(req::ContentType::Json),
-        user_agent: None,
-        headers: Some(headers),
-        method: req::Method::Post,
+/// The providers are equal if they are the same service with the same
+/// configuration. The credentials are compared in constant time.
+impl PartialEq for Provider {
+    fn eq(&self, other: &Provider) -> bool {
+        mem::discriminant(self) == mem::discriminant(other) && self.settings() == other.settings()
     }
 }
 
-parse_noop!(nowlinks_parse);
-request!(
-    nowlinks_req,
-    req::Method::Get,
-    "http://nowlinks.net/api?url={}"
-);
+impl Eq for Provider {}
 
-parse_noop!(phxcoin_parse);
-request!(
-    phxcoin_req,
-    req::Method::Get,
-    "http://phx.co.in/shrink.asp?url={}"
-);
+/// The credentials are not hashed.
+impl Hash for Provider {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        self.settings().hash(state);
+    }
+}
 
-parse_xml_tag!(psbeco_parse, "ShortUrl");
-request!(
-    psbeco_req,
-    req::Method::Get,
-    "http://psbe.co/API.asmx/CreateUrl?real_url={}"
-);
+/// Formats the secret values, masked unless revealed.
+trait DebugSecret {
+    fn fmt_secret(&self, f: &mut fmt::Formatter, reveal: bool) -> fmt::Result;
+}
 
-parse_noop!(scoop_parse);
-request!(
+impl DebugSecret for String {
+    fn fmt_secret(&self, f: &mut fmt::Formatter, reveal: bool) -> fmt::Result {
+        if reveal {
+            fmt::Debug::fmt(self, f)
+        } else {
+            f.write_str("***")
+        }
+    }
+}
+
+impl DebugSecret for Option<String> {
+    fn fmt_secret(&self, f: &mut fmt::Formatter, reveal: bool) -> fmt::Result {
+        match *self {
+            Some(ref secret) => f
+                .debug_tuple("Some")
+                .field(&Reveal(secret, reveal))
+                .finish(),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+impl DebugSecret for YourlsAuth {
+    fn fmt_secret(&self, f: &mut fmt::Formatter, reveal: bool) -> fmt::Result {
+        match *self {
+            YourlsAuth::Public => f.write_str("Public"),
+            YourlsAuth::Signature(ref signature) => f
+                .debug_tuple("Signature")
+                .field(&Reveal(signature, reveal))
+                .finish(),
+            YourlsAuth::Password {
+                ref username,
+                ref password,
+            } => f
+                .debug_struct("Password")
+                .field("username", username)
+                .field("password", &Reveal(password, reveal))
+                .finish(),
+        }
+    }
+}
+
+impl DebugSecret for Provider {
+    #[allow(deprecated)]
+    fn fmt_secret(&self, f: &mut fmt::Formatter, reveal: bool) -> fmt::Result {
+        match *self {
+            Provider::Abv8 => f.write_str("Abv8"),
+            Provider::AdfLy {
+                ref api_key,
+                ref user_id,
+                ref advert_type,
+            } => f
+                .debug_struct("AdfLy")
+                .field("api_key", &Reveal(api_key, reveal))
+                .field("user_id", user_id)
+                .field("advert_type", advert_type)
+                .finish(),
+            Provider::BamBz => f.write_str("BamBz"),
+            Provider::BitLy { ref token } => f
+                .debug_struct("BitLy")
+                .field("token", &Reveal(token, reveal))
+                .finish(),
+            Provider::BitUrl => f.write_str("BitUrl"),
+            Provider::Blink {
+                ref api_token,
+                ref domain_id,
+            } => f
+                .debug_struct("Blink")
+                .field("api_token", &Reveal(api_token, reveal))
+                .field("domain_id", domain_id)
+                .finish(),
+            Provider::Bmeo => f.write_str("Bmeo"),
+            Provider::Branch { ref key } => f
+                .debug_struct("Branch")
+                .field("key", &Reveal(key, reveal))
+                .finish(),
+            Provider::ChhotoUrl {
+                ref api_key,
+                ref host,
+            } => f
+                .debug_struct("ChhotoUrl")
+                .field("api_key", &Reveal(api_key, reveal))
+                .field("host", host)
+                .finish(),
+            Provider::CleanUri => f.write_str("CleanUri"),
+            Provider::ClckRu => f.write_str("ClckRu"),
+            Provider::Cuttly { ref api_key } => f
+                .debug_struct("Cuttly")
+                .field("api_key", &Reveal(api_key, reveal))
+                .finish(),
+            Provider::Fake { ref pattern } => {
+                f.debug_struct("Fake").field("pattern", pattern).finish()
+            }
+            Provider::FifoCc => f.write_str("FifoCc"),
+            Provider::GgGg => f.write_str("GgGg"),
+            Provider::GooGl { ref api_key } => f
+                .debug_struct("GooGl")
+                .field("api_key", &Reveal(api_key, reveal))
+                .finish(),
+            Provider::Kutt {
+                ref api_key,
+                ref host,
+            } => f
+                .debug_struct("Kutt")
+                .field("api_key", &Reveal(api_key, reveal))
+                .field("host", host)
+                .finish(),
+            Provider::HecSu => f.write_str("HecSu"),
+            Provider::HmmRs => f.write_str("HmmRs"),
+            Provider::IsGd => f.write_str("IsGd"),
+            Provider::LihiCc {
+                ref token,
+                ref split_urls,
+            } => f
+                .debug_struct("LihiCc")
+                .field("token", &Reveal(token, reveal))
+                .field("split_urls", split_urls)
+                .finish(),
+            Provider::LnnkIn => f.write_str("LnnkIn"),
+            Provider::Local { ref base_url, .. } => f
+                .debug_struct("Local")
+                .field("base_url", base_url)
+                .finish_non_exhaustive(),
+            Provider::MgnetMe => f.write_str("MgnetMe"),
+            Provider::N9Cl { ref api_key } => f
+                .debug_struct("N9Cl")
+                .field("api_key", &Reveal(api_key, reveal))
+                .finish(),
+            Provider::NowLinks => f.write_str("NowLinks"),
+            Provider::OneLink {
+                ref template_id,
+                ref api_token,
+            } => f
+                .debug_struct("OneLink")
+                .field("template_id", template_id)
+                .field("api_token", &Reveal(api_token, reveal))
+                .finish(),
+            Provider::OuoIo { ref api_key } => f
+                .debug_struct("OuoIo")
+                .field("api_key", &Reveal(api_key, reveal))
+                .finish(),
+            Provider::PhxCoIn => f.write_str("PhxCoIn"),
+            Provider::PsbeCo => f.write_str("PsbeCo"),
+            Provider::PseIs { ref token } => f
+                .debug_struct("PseIs")
+                .field("token", &Reveal(token, reveal))
+                .finish(),
+            Provider::SnipLy {
+                ref token,
+                ref cta,
+                ref campaign,
+            } => f
+                .debug_struct("SnipLy")
+                .field("token", &Reveal(token, reveal))
+                .field("cta", cta)
+                .field("campaign", campaign)
+                .finish(),
+            Provider::SpooMe => f.write_str("SpooMe"),
+            Provider::SCoop => f.write_str("SCoop"),
+            Provider::Rebrandly {
+                ref api_key,
+                ref domain,
+            } => f
+                .debug_struct("Rebrandly")
+                .field("api_key", &Reveal(api_key, reveal))
+                .field("domain", domain)
+                .finish(),
+            Provider::Rlu => f.write_str("Rlu"),
+            Provider::ShortIo {
+                ref api_key,
+                ref domain,
+            } => f
+                .debug_struct("ShortIo")
+                .field("api_key", &Reveal(api_key, reveal))
+                .field("domain", domain)
+                .finish(),
+            Provider::ShorteSt { ref api_token } => f
+                .debug_struct("ShorteSt")
+                .field("api_token", &Reveal(api_token, reveal))
+                .finish(),
+            Provider::ShrtcoDe => f.write_str("ShrtcoDe"),
+            Provider::SId {
+                ref client_id,
+                ref api_key,
+            } => f
+                .debug_struct("SId")
+                .field("client_id", client_id)
+                .field("api_key", &Reveal(api_key, reveal))
+                .finish(),
+            Provider::SirBz => f.write_str("SirBz"),
+            Provider::T2m { ref api_key } => f
+                .debug_struct("T2m")
+                .field("api_key", &Reveal(api_key, reveal))
+                .finish(),
+            Provider::TinyUrl => f.write_str("TinyUrl"),
+            Provider::TinyUrlApi { ref token } => f
+                .debug_struct("TinyUrlApi")
+                .field("token", &Reveal(token, reveal))
+                .finish(),
+            Provider::TinyPh => f.write_str("TinyPh"),
+            Provider::TnyIm => f.write_str("TnyIm"),
+            Provider::UrlShortenerIo => f.write_str("UrlShortenerIo"),
+            Provider::VGd => f.write_str("VGd"),
+            Provider::VHt => f.write_str("VHt"),
+            Provider::VurlCom => f.write_str("VurlCom"),
+            Provider::Yourls {
+                ref base_url,
+                ref auth,
+            } => f
+                .debug_struct("Yourls")
+                .field("base_url", base_url)
+                .field("auth", &Reveal(auth, reveal))
+                .finish(),
+        }
+    }
+}
+
+/// Formats the value with its secrets masked unless revealed.
+struct Reveal<'a, T>(&'a T, bool);
+
+impl<'a, T: DebugSecret> fmt::Debug for Reveal<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_secret(f, self.1)
+    }
+}
+
+impl fmt::Debug for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_secret(f, false)
+    }
+}
+
+impl fmt::Debug for YourlsAuth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_secret(f, false)
+    }
+}
+
+/// Formats a provider with its credentials shown, see `Provider::reveal`.
+pub struct RevealedProvider<'a>(&'a Provider);
+
+impl<'a> fmt::Debug for RevealedProvider<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_secret(f, true)
+    }
+}
+
+impl Provider {
+    /// Returns `true` if the provider is able to honour the link option.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::{options::OptionKind, providers::Provider};
+    ///
+    /// assert!(Provider::IsGd.supports(OptionKind::Alias));
+    /// assert!(!Provider::IsGd.supports(OptionKind::Password));
+    /// ```
+    pub fn supports(&self, option: OptionKind) -> bool {
+        match option {
+            OptionKind::Title => matches!(
+                *self,
+                Provider::BitLy { .. }
+                    | Provider::Branch { .. }
+                    | Provider::OneLink { .. }
+                    | Provider::PseIs { .. }
+                    | Provider::Rebrandly { .. }
+                    | Provider::ShortIo { .. }
+                    | Provider::TnyIm
+                    | Provider::Yourls { .. }
+            ),
+            OptionKind::Tags => matches!(
+                *self,
+                Provider::BitLy { .. } | Provider::Branch { .. } | Provider::ShortIo { .. }
+            ),
+            OptionKind::Alias => matches!(
+                *self,
+                Provider::Blink { .. }
+                    | Provider::Branch { .. }
+                    | Provider::ChhotoUrl { .. }
+                    | Provider::Cuttly { .. }
+                    | Provider::GgGg
+                    | Provider::IsGd
+                    | Provider::VGd
+                    | Provider::Kutt { .. }
+                    | Provider::LihiCc { .. }
+                    | Provider::LnnkIn
+                    | Provider::Local { .. }
+                    | Provider::Fake { .. }
+                    | Provider::OneLink { .. }
+                    | Provider::PseIs { .. }
+                    | Provider::Rebrandly { .. }
+                    | Provider::SId { .. }
+                    | Provider::ShortIo { .. }
+                    | Provider::SpooMe
+                    | Provider::T2m { .. }
+                    | Provider::TinyUrlApi { .. }
+                    | Provider::TnyIm
+                    | Provider::Yourls { .. }
+            ),
+            OptionKind::Expiration => matches!(
+                *self,
+                Provider::ChhotoUrl { .. }
+                    | Provider::Kutt { .. }
+                    | Provider::ShortIo { .. }
+                    | Provider::TinyUrlApi { .. }
+            ),
+            OptionKind::Domain => matches!(
+                *self,
+                Provider::Kutt { .. }
+                    | Provider::Rebrandly { .. }
+                    | Provider::ShrtcoDe
+                    | Provider::TinyUrlApi { .. }
+            ),
+            OptionKind::Password => matches!(
+                *self,
+                Provider::Kutt { .. } | Provider::ShortIo { .. } | Provider::SpooMe
+            ),
+            OptionKind::MaxClicks => {
+                matches!(*self, Provider::ShortIo { .. } | Provider::SpooMe)
+            }
+            OptionKind::Private => matches!(*self, Provider::ShortIo { .. }),
+            OptionKind::Description => matches!(
+                *self,
+                Provider::Branch { .. }
+                    | Provider::Kutt { .. }
+                    | Provider::OneLink { .. }
+                    | Provider::PseIs { .. }
+            ),
+            OptionKind::ReuseExisting => {
+                matches!(*self, Provider::Kutt { .. } | Provider::ShortIo { .. })
+            }
+            OptionKind::LogStats => matches!(*self, Provider::IsGd | Provider::VGd),
+            // The preview is a suffix of the short URL, which the requests and
+            // the responses can't carry: `UrlShortener` adds it (see
+            // `previews_with_hyphen`).
+            OptionKind::Preview => false,
+            OptionKind::DeepLink => {
+                matches!(*self, Provider::Branch { .. } | Provider::OneLink { .. })
+            }
+        }
+    }
+
+    /// Returns the provider with its api key, token or signature replaced by
+    /// the credential. The providers without authentication are returned
+    /// unchanged.
+    #[allow(deprecated)]
+    fn with_credential(&self, credential: &Secret) -> Provider {
+        let mut provider = self.clone();
+        let token = credential.expose().to_owned();
+
+        match provider {
+            Provider::AdfLy {
+                ref mut api_key, ..
+            }
+            | Provider::ChhotoUrl {
+                ref mut api_key, ..
+            }
+            | Provider::Cuttly { ref mut api_key }
+            | Provider::GooGl { ref mut api_key }
+            | Provider::Kutt {
+                ref mut api_key, ..
+            }
+            | Provider::OuoIo { ref mut api_key }
+            | Provider::Rebrandly {
+                ref mut api_key, ..
+            }
+            | Provider::SId {
+                ref mut api_key, ..
+            }
+            | Provider::ShortIo {
+                ref mut api_key, ..
+            }
+            | Provider::T2m { ref mut api_key } => *api_key = token,
+            Provider::Blink {
+                ref mut api_token, ..
+            }
+            | Provider::OneLink {
+                ref mut api_token, ..
+            }
+            | Provider::ShorteSt { ref mut api_token } => *api_token = token,
+            Provider::BitLy { token: ref mut t }
+            | Provider::LihiCc {
+                token: ref mut t, ..
+            }
+            | Provider::PseIs { token: ref mut t }
+            | Provider::SnipLy {
+                token: ref mut t, ..
+            }
+            | Provider::TinyUrlApi { token: ref mut t } => *t = token,
+            Provider::Branch { ref mut key } => *key = token,
+            Provider::N9Cl { ref mut api_key } => *api_key = Some(token),
+            Provider::Yourls { ref mut auth, .. } => match *auth {
+                YourlsAuth::Public => {}
+                YourlsAuth::Signature(ref mut signature) => *signature = token,
+                YourlsAuth::Password {
+                    ref mut password, ..
+                } => *password = token,
+            },
+            _ => {}
+        }
+        provider
+    }
+
+    /// Returns the provider formatter which shows the credentials in the
+    /// `Debug` output, which are masked otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+    /// assert_eq!(format!("{:?}", provider), "BitLy { token: *** }");
+    /// assert_eq!(format!("{:?}", provider.reveal()), r#"BitLy { token: "MY_TOKEN" }"#);
+    /// ```
+    pub fn reveal(&self) -> RevealedProvider<'_> {
+        RevealedProvider(self)
+    }
+
+    /// Returns the URL schemes the provider is meant to shorten.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// assert_eq!(Provider::IsGd.schemes(), &["http", "https"]);
+    /// assert_eq!(Provider::MgnetMe.schemes(), &["magnet"]);
+    /// ```
+    pub fn schemes(&self) -> &'static [&'static str] {
+        match *self {
+            Provider::MgnetMe => &["magnet"],
+            _ => &["http", "https"],
+        }
+    }
+
+    /// Returns the maximum number of URLs the provider is able to shorten in a
+    /// single bulk request, or `None` if the provider has no bulk API.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// assert_eq!(Provider::IsGd.max_bulk_size(), None);
+    /// ```
+    pub fn max_bulk_size(&self) -> Option<usize> {
+        match *self {
+            Provider::ShortIo { .. } => Some(1000),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of requests the provider accepts within a time
+    /// window, as documented for its free plan, or `None` if the provider
+    /// documents no limit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use urlshortener::providers::{Provider, Quota};
+    ///
+    /// assert_eq!(
+    ///     Provider::IsGd.default_quota(),
+    ///     Some(Quota::new(200, Duration::from_secs(3600)))
+    /// );
+    /// ```
+    pub fn default_quota(&self) -> Option<Quota> {
+        match *self {
+            Provider::IsGd | Provider::VGd => Some(Quota::new(200, Duration::from_secs(3600))),
+            Provider::Cuttly { .. } => Some(Quota::new(3, Duration::from_secs(60))),
+            Provider::BitLy { .. } => Some(Quota::new(100, Duration::from_secs(60))),
+            Provider::TinyUrlApi { .. } => Some(Quota::new(600, Duration::from_secs(3600))),
+            Provider::ShortIo { .. } => Some(Quota::new(50, Duration::from_secs(10))),
+            _ => None,
+        }
+    }
+
+    /// Returns how quickly the provider usually responds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::{Provider, TimeoutTier};
+    ///
+    /// assert_eq!(Provider::IsGd.timeout_tier(), TimeoutTier::Fast);
+    /// assert_eq!(Provider::TnyIm.timeout_tier(), TimeoutTier::Slow);
+    /// ```
+    pub fn timeout_tier(&self) -> TimeoutTier {
+        match *self {
+            Provider::BitLy { .. }
+            | Provider::ClckRu
+            | Provider::Cuttly { .. }
+            | Provider::IsGd
+            | Provider::Rebrandly { .. }
+            | Provider::ShortIo { .. }
+            | Provider::VGd => TimeoutTier::Fast,
+            Provider::PsbeCo | Provider::TnyIm => TimeoutTier::Slow,
+            _ => TimeoutTier::Normal,
+        }
+    }
+
+    /// Returns the home page of the service, where the credentials may be
+    /// obtained. For the self hosted providers, this is the home page of the
+    /// project.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// assert_eq!(Provider::IsGd.homepage(), "https://is.gd");
+    /// ```
+    #[allow(deprecated)]
+    pub fn homepage(&self) -> &'static str {
+        match *self {
+            Provider::Abv8 => "http://abv8.me",
+            Provider::AdfLy { .. } => "https://adf.ly",
+            Provider::BamBz => "https://bam.bz",
+            Provider::BitLy { .. } => "https://bitly.com",
+            Provider::BitUrl => "https://biturl.top",
+            Provider::Blink { .. } => "https://www.bl.ink",
+            Provider::Bmeo => "http://bmeo.org",
+            Provider::Branch { .. } => "https://branch.io",
+            Provider::ChhotoUrl { .. } => "https://github.com/SinTan1729/chhoto-url",
+            Provider::CleanUri => "https://cleanuri.com",
+            Provider::ClckRu => "https://clck.ru",
+            Provider::Cuttly { .. } => "https://cutt.ly",
+            Provider::Fake { .. } => "https://github.com/iddm/urlshortener-rs",
+            Provider::FifoCc => "https://fifo.cc",
+            Provider::GgGg => "http://gg.gg",
+            Provider::GooGl { .. } => "https://goo.gl",
+            Provider::HmmRs => "http://hmm.rs",
+            Provider::HecSu => "https://hec.su",
+            Provider::IsGd => "https://is.gd",
+            Provider::Kutt { .. } => "https://kutt.it",
+            Provider::LihiCc { .. } => "https://lihi.cc",
+            Provider::LnnkIn => "https://lnnk.in",
+            Provider::Local { .. } => "https://github.com/iddm/urlshortener-rs",
+            Provider::MgnetMe => "http://mgnet.me",
+            Provider::N9Cl { .. } => "https://n9.cl",
+            Provider::NowLinks => "http://nowlinks.net",
+            Provider::OneLink { .. } => "https://www.appsflyer.com/products/onelink",
+            Provider::OuoIo { .. } => "https://ouo.io",
+            Provider::PhxCoIn => "http://phx.co.in",
+            Provider::PsbeCo => "http://psbe.co",
+            Provider::PseIs { .. } => "https://picsee.io",
+            Provider::SnipLy { .. } => "https://snip.ly",
+            Provider::SpooMe => "https://spoo.me",
+            Provider::SCoop => "http://s.coop",
+            Provider::ShortIo { .. } => "https://short.io",
+            Provider::ShorteSt { .. } => "https://shorte.st",
+            Provider::ShrtcoDe => "https://shrtco.de",
+            Provider::SId { .. } => "https://s.id",
+            Provider::SirBz => "http://sirbz.com",
+            Provider::Rebrandly { .. } => "https://www.rebrandly.com",
+            Provider::Rlu => "http://rlu.ru",
+            Provider::T2m { .. } => "https://t2m.io",
+            Provider::TinyUrl => "https://tinyurl.com",
+            Provider::TinyUrlApi { .. } => "https://tinyurl.com",
+            Provider::TinyPh => "http://tiny.ph",
+            Provider::TnyIm => "http://tny.im",
+            Provider::UrlShortenerIo => "http://url-shortener.io",
+            Provider::VGd => "https://v.gd",
+            Provider::VHt => "https://v.ht",
+            Provider::VurlCom => "https://vurl.com",
+            Provider::Yourls { .. } => "https://yourls.org",
+        }
+    }
+
+    /// Returns the documentation of the service API, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// assert_eq!(
+    ///     Provider::IsGd.api_docs(),
+    ///     Some("https://is.gd/apishorteningreference.php")
+    /// );
+    /// ```
+    pub fn api_docs(&self) -> Option<&'static str> {
+        match *self {
+            Provider::AdfLy { .. } => Some("https://adf.ly/publisher/tools#tools-api"),
+            Provider::BitLy { .. } => Some("https://dev.bitly.com"),
+            Provider::BitUrl => Some("https://biturl.top/api"),
+            Provider::Blink { .. } => Some("https://developer.bl.ink"),
+            Provider::Branch { .. } => {
+                Some("https://help.branch.io/developers-hub/reference/createdeeplinkurl")
+            }
+            Provider::ChhotoUrl { .. } => {
+                Some("https://github.com/SinTan1729/chhoto-url#instructions-for-cli-usage")
+            }
+            Provider::CleanUri => Some("https://cleanuri.com/docs"),
+            Provider::Cuttly { .. } => Some("https://cutt.ly/api-documentation/cuttly-links-api"),
+            Provider::IsGd => Some("https://is.gd/apishorteningreference.php"),
+            Provider::Kutt { .. } => Some("https://docs.kutt.it"),
+            Provider::MgnetMe => Some("http://mgnet.me/api.html"),
+            Provider::N9Cl { .. } => Some("https://n9.cl/en/api"),
+            Provider::OneLink { .. } => {
+                Some("https://dev.appsflyer.com/hc/reference/create-onelink-attribution-link")
+            }
+            Provider::OuoIo { .. } => Some("https://ouo.io/tools/api"),
+            Provider::PseIs { .. } => Some("https://picsee.io/developers"),
+            Provider::SnipLy { .. } => Some("https://snip.ly/api"),
+            Provider::SpooMe => Some("https://spoo.me/api"),
+            Provider::ShortIo { .. } => Some("https://developers.short.io"),
+            Provider::ShorteSt { .. } => Some("https://shorte.st/tools/api"),
+            Provider::ShrtcoDe => Some("https://shrtco.de/docs"),
+            Provider::SId { .. } => Some("https://developer.s.id"),
+            Provider::Rebrandly { .. } => Some("https://developers.rebrandly.com"),
+            Provider::T2m { .. } => Some("https://t2m.io/api-docs"),
+            Provider::TinyUrlApi { .. } => Some("https://tinyurl.com/app/dev"),
+            Provider::TnyIm => Some("http://tny.im/aboutapi.php"),
+            Provider::VGd => Some("https://v.gd/apishorteningreference.php"),
+            Provider::Yourls { .. } => Some("https://yourls.org/docs/guide/advanced/api"),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the provider earns from the created links by showing
+    /// adverts to the visitors before redirecting them. Such providers are
+    /// never in the `PROVIDERS` list and must be selected explicitly.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// assert!(!Provider::IsGd.is_monetized());
+    /// ```
+    pub fn is_monetized(&self) -> bool {
+        matches!(
+            *self,
+            Provider::AdfLy { .. } | Provider::OuoIo { .. } | Provider::ShorteSt { .. }
+        )
+    }
+
+    /// Returns `true` if the provider shows its own content, like a call to
+    /// action, over the target page instead of redirecting to it directly.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use urlshortener::providers::Provider;
+    ///
+    /// assert!(!Provider::IsGd.shows_overlay());
+    /// ```
+    pub fn shows_overlay(&self) -> bool {
+        matches!(*self, Provider::SnipLy { .. })
+    }
+
+    /// Returns whether the short URLs of the provider lead to a preview page
+    /// of the destination when a hyphen is appended to them.
+    #[cfg(feature = "client")]
+    pub(crate) fn previews_with_hyphen(&self) -> bool {
+        matches!(*self, Provider::IsGd | Provider::VGd)
+    }
+
+    /// Returns whether the provider creates the short URLs without sending a
+    /// request, see `shorten_locally`.
+    #[cfg(feature = "client")]
+    pub(crate) fn is_local(&self) -> bool {
+        matches!(*self, Provider::Fake { .. } | Provider::Local { .. })
+    }
+
+    /// Returns whether the provider is an API which tolerates the headers it
+    /// does not know, like `X-Correlation-ID`. The services without an api
+    /// key or a host to configure are web forms which may not.
+    #[cfg(feature = "client")]
+    pub(crate) fn tolerates_extra_headers(&self) -> bool {
+        matches!(
+            *self,
+            Provider::AdfLy { .. }
+                | Provider::BitLy { .. }
+                | Provider::Blink { .. }
+                | Provider::Branch { .. }
+                | Provider::ChhotoUrl { .. }
+                | Provider::Cuttly { .. }
+                | Provider::Kutt { .. }
+                | Provider::LihiCc { .. }
+                | Provider::N9Cl { .. }
+                | Provider::OneLink { .. }
+                | Provider::OuoIo { .. }
+                | Provider::PseIs { .. }
+                | Provider::Rebrandly { .. }
+                | Provider::SId { .. }
+                | Provider::ShortIo { .. }
+                | Provider::ShorteSt { .. }
+                | Provider::SnipLy { .. }
+                | Provider::T2m { .. }
+                | Provider::TinyUrlApi { .. }
+                | Provider::Yourls { .. }
+        )
+    }
+
+    /// Converts the Provider variant into its domain name equivilant
+    #[allow(deprecated)]
+    pub fn to_name(&self) -> &str {
+        match *self {
+            Provider::Abv8 => "abv8.me",
+            Provider::AdfLy { .. } => "adf.ly",
+            Provider::BamBz => "bam.bz",
+            Provider::BitLy { .. } => "bitly.com",
+            Provider::BitUrl => "biturl.top",
+            Provider::Blink { .. } => "bl.ink",
+            Provider::Bmeo => "bmeo.org",
+            Provider::Branch { .. } => "branch.io",
+            Provider::ChhotoUrl { ref host, .. } => host.rsplit("//").next().unwrap_or_default(),
+            Provider::CleanUri => "cleanuri.com",
+            Provider::ClckRu => "clck.ru",
+            Provider::Cuttly { .. } => "cutt.ly",
+            Provider::Fake { .. } => "fake",
+            Provider::FifoCc => "fifo.cc",
+            Provider::GgGg => "gg.gg",
+            Provider::GooGl { .. } => "goo.gl",
+            Provider::HmmRs => "hmm.rs",
+            Provider::HecSu => "hec.su",
+            Provider::IsGd => "is.gd",
+            Provider::Kutt { ref host, .. } => host
+                .as_ref()
+                .map(|h| h.rsplit("//").next().unwrap_or_default())
+                .unwrap_or("kutt.it"),
+            Provider::LihiCc { .. } => "lihi.cc",
+            Provider::LnnkIn => "lnnk.in",
+            Provider::Local { ref base_url, .. } => {
+                base_url.rsplit("//").next().unwrap_or_default()
+            }
+            Provider::MgnetMe => "mgnet.me",
+            Provider::N9Cl { .. } => "n9.cl",
+            Provider::NowLinks => "nowlinks.net",
+            Provider::OneLink { .. } => "onelink.me",
+            Provider::OuoIo { .. } => "ouo.io",
+            Provider::PhxCoIn => "phx.co.in",
+            Provider::PsbeCo => "psbe.co",
+            Provider::PseIs { .. } => "pse.is",
+            Provider::SnipLy { .. } => "snip.ly",
+            Provider::SpooMe => "spoo.me",
+            Provider::SCoop => "s.coop",
+            Provider::ShortIo { ref domain, .. } => domain,
+            Provider::ShorteSt { .. } => "shorte.st",
+            Provider::ShrtcoDe => "shrtco.de",
+            Provider::SId { .. } => "s.id",
+            Provider::SirBz => "sirbz.com",
+            Provider::Rebrandly { ref domain, .. } => domain.as_deref().unwrap_or("rebrand.ly"),
+            Provider::Rlu => "rlu.ru",
+            Provider::T2m { .. } => "t2m.io",
+            Provider::TinyUrl => "tinyurl.com",
+            Provider::TinyUrlApi { .. } => "tinyurl.com",
+            Provider::TinyPh => "tiny.ph",
+            Provider::TnyIm => "tny.im",
+            Provider::UrlShortenerIo => "url-shortener.io",
+            Provider::VGd => "v.gd",
+            Provider::VHt => "v.ht",
+            Provider::VurlCom => "vurl.com",
+            Provider::Yourls { ref base_url, .. } => base_url
+                .rsplit("//")
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('/'),
+        }
+    }
+}
+
+parse_noop!(abv8_parse);
+request!(abv8_req, req::Method::Get, "http://abv8.me/?url={}");
+
+// The api responds with the short url or with the "error" text.
+fn adfly_parse(res: &str) -> Option<String> {
+    let res = res.trim();
+    if res.starts_with("http") {
+        Some(res.to_owned())
+    } else {
+        None
+    }
+}
+fn adfly_req(url: &str, api_key: &str, user_id: &str, advert_type: AdfLyAdvert) -> req::Request {
+    let query = encode::Serializer::new(String::new())
+        .append_pair("key", api_key)
+        .append_pair("uid", user_id)
+        .append_pair("advert_type", advert_type.as_str())
+        .append_pair("domain", "adf.ly")
+        .append_pair("url", url)
+        .finish();
+
+    req::Request {
+        url: format!("https://api.adf.ly/api.php?{}", query),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+parse_json_tag!(bambz_parse, "url", "");
+request!(
+    bambz_req,
+    req::Method::Post,
+    "https://bam.bz/api/short",
+    "target={}",
+    req::ContentType::FormUrlEncoded
+);
+
+/// Makes the header value of a credential, hidden from the `Debug` output. A
+/// credential which is not a valid header value is sent empty, for the
+/// provider to reject it, instead of panicking.
+fn secret_header(value: &str) -> HeaderValue {
+    let mut value = HeaderValue::from_str(value).unwrap_or(HeaderValue::from_static(""));
+    value.set_sensitive(true);
+    value
+}
+
+fn bearer_headers(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::AUTHORIZATION,
+        secret_header(&format!("Bearer {}", token)),
+    );
+    headers
+}
+
+/// Parses the json response of the bit.ly API, mapping the documented error
+/// messages to the errors.
+fn bitly_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    if let Some(link) = json.get("link").and_then(|l| l.as_str()) {
+        return Ok(link.to_owned());
+    }
+
+    let message = json
+        .get("message")
+        .and_then(|m| m.as_str())
+        .ok_or(ProviderError::Deserialize)?;
+    let error = match message {
+        "ALREADY_A_BITLY_LINK" => BitLyError::AlreadyABitlyLink,
+        "INVALID_ARG_LONG_URL" => BitLyError::InvalidLongUrl,
+        "FORBIDDEN" => BitLyError::Forbidden,
+        m if m.starts_with("INVALID_ARG_") => BitLyError::InvalidArgument,
+        m if m.ends_with("_LIMIT_EXCEEDED") => BitLyError::LimitExceeded,
+        _ => return Err(ProviderError::Deserialize),
+    };
+    Err(ProviderError::Provider(ProviderCode::BitLy(error)))
+}
+fn bitly_req(url: &str, token: &str, options: &ShortenOptions) -> req::Request {
+    let mut body = serde_json::json!({ "long_url": url });
+    if let Some(ref title) = options.title {
+        body["title"] = title.as_str().into();
+    }
+    if !options.tags.is_empty() {
+        body["tags"] = options.tags.clone().into();
+    }
+
+    req::Request {
+        url: "https://api-ssl.bitly.com/v4/bitlinks".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(token)),
+        method: req::Method::Post,
+    }
+}
+
+/// Returns the bitlink id of the short URL: the API identifies bitlinks by
+/// their domain and hash, without the scheme, the query, the fragment and a
+/// trailing slash.
+#[cfg(feature = "url")]
+fn bitlink_id(short_url: &str) -> String {
+    let short_url = short_url.trim();
+    let parsed = if short_url.contains("://") {
+        url::Url::parse(short_url)
+    } else {
+        url::Url::parse(&format!("https://{}", short_url))
+    };
+    match parsed {
+        Ok(ref url) => match url.host_str() {
+            Some(host) => format!("{}{}", host, url.path().trim_end_matches('/')),
+            None => short_url.to_owned(),
+        },
+        Err(_) => short_url.to_owned(),
+    }
+}
+
+/// Returns the bitlink id of the short URL: the API identifies bitlinks by
+/// their domain and hash, without the scheme, the query, the fragment and a
+/// trailing slash.
+#[cfg(not(feature = "url"))]
+fn bitlink_id(short_url: &str) -> String {
+    let short_url = short_url.trim();
+    let rest = match short_url.find("://") {
+        Some(i) => &short_url[i + 3..],
+        None => short_url,
+    };
+    let rest = &rest[..rest.find(['?', '#']).unwrap_or(rest.len())];
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = match authority.rfind('@') {
+        Some(i) => &authority[i + 1..],
+        None => authority,
+    };
+    let host = host.split(':').next().unwrap_or(host);
+    format!(
+        "{}{}",
+        host.to_ascii_lowercase(),
+        path.trim_end_matches('/')
+    )
+}
+
+parse_json_tag!(bitly_expand_parse, "long_url", "");
+fn bitly_expand_req(short_url: &str, token: &str) -> req::Request {
+    let bitlink = bitlink_id(short_url);
+
+    req::Request {
+        url: "https://api-ssl.bitly.com/v4/expand".to_owned(),
+        body: Some(serde_json::json!({ "bitlink_id": bitlink }).to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(token)),
+        method: req::Method::Post,
+    }
+}
+
+fn bitly_stats_parse(res: &str) -> Option<LinkStats> {
+    let json: serde_json::Value = serde_json::from_str(res).ok()?;
+    let clicks = json.get("total_clicks")?.as_u64()?;
+    Some(LinkStats { clicks })
+}
+fn bitly_stats_req(short_url: &str, token: &str, period: &StatsPeriod) -> req::Request {
+    let bitlink = encode::path_segment(&bitlink_id(short_url));
+    let units = period.units.map(i64::from).unwrap_or(-1);
+
+    req::Request {
+        url: format!(
+            "https://api-ssl.bitly.com/v4/bitlinks/{}/clicks/summary?unit={}&units={}",
+            bitlink,
+            period.unit.as_str(),
+            units
+        ),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: Some(bearer_headers(token)),
+        method: req::Method::Get,
+    }
+}
+
+parse_json_tag!(blink_parse, "short_link", "");
+fn blink_req(url: &str, api_token: &str, domain_id: u64, options: &ShortenOptions) -> req::Request {
+    let mut body = serde_json::json!({ "url": url, "domain_id": domain_id });
+    if let Some(ref alias) = options.alias {
+        body["alias"] = alias.as_str().into();
+    }
+
+    req::Request {
+        url: "https://app.bl.ink/api/v4/shorten".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(api_token)),
+        method: req::Method::Post,
+    }
+}
+
+parse_json_tag!(bmeo_parse, "short", "");
+request!(bmeo_req, req::Method::Get, "http://bmeo.org/api.php?url={}");
+
+parse_json_tag!(branch_parse, "url", "");
+fn branch_req(url: &str, key: &str, options: &ShortenOptions) -> req::Request {
+    let mut data = serde_json::json!({ "$fallback_url": url, "$desktop_url": url });
+    if let Some(ref title) = options.title {
+        data["$og_title"] = title.as_str().into();
+    }
+    if let Some(ref description) = options.description {
+        data["$og_description"] = description.as_str().into();
+    }
+
+    let mut body = serde_json::json!({ "branch_key": key });
+    if let Some(ref alias) = options.alias {
+        body["alias"] = alias.as_str().into();
+    }
+    if !options.tags.is_empty() {
+        body["tags"] = options.tags.clone().into();
+    }
+    if let Some(ref deep_link) = options.deep_link {
+        if let Some(ref ios_url) = deep_link.ios_url {
+            data["$ios_url"] = ios_url.as_str().into();
+        }
+        if let Some(android_url) = deep_link.android_fallback() {
+            data["$android_url"] = android_url.into();
+        }
+        if let Some(ref desktop_url) = deep_link.desktop_url {
+            data["$desktop_url"] = desktop_url.as_str().into();
+        }
+        for (key, value) in &deep_link.data {
+            data[key] = value.as_str().into();
+        }
+        if let Some(ref campaign) = deep_link.campaign {
+            body["campaign"] = campaign.as_str().into();
+        }
+        if let Some(ref channel) = deep_link.channel {
+            body["channel"] = channel.as_str().into();
+        }
+    }
+    body["data"] = data;
+
+    req::Request {
+        url: "https://api2.branch.io/v1/url".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: None,
+        method: req::Method::Post,
+    }
+}
+
+fn chhotourl_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    if let Some(short_url) = json.get("shorturl").and_then(|s| s.as_str()) {
+        return Ok(short_url.to_owned());
+    }
+
+    match json.get("reason").and_then(|r| r.as_str()) {
+        Some(reason) if reason.contains("exists") => Err(ProviderError::AliasTaken),
+        _ => Err(ProviderError::Deserialize),
+    }
+}
+fn chhotourl_req(url: &str, api_key: &str, host: &str, options: &ShortenOptions) -> req::Request {
+    let mut headers = HeaderMap::new();
+    headers.insert("X-API-Key", secret_header(api_key));
+
+    let mut body = serde_json::json!({
+        "shortlink": options.alias.as_deref().unwrap_or(""),
+        "longlink": url,
+    });
+    if let Some(ref expires) = options.expires {
+        body["expiry_delay"] = expires.duration_from_now().as_secs().into();
+    }
+
+    req::Request {
+        url: format!("{}/api/new", host.trim_end_matches('/')),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(headers),
+        method: req::Method::Post,
+    }
+}
+
+parse_json_tag!(cleanuri_parse, "result_url", "");
+fn cleanuri_req(url: &str) -> req::Request {
+    let body = encode::Serializer::new(String::new())
+        .append_pair("url", url)
+        .finish();
+
+    req::Request {
+        url: "https://cleanuri.com/api/v1/shorten".to_owned(),
+        body: Some(body),
+        content_type: Some(req::ContentType::FormUrlEncoded),
+        user_agent: None,
+        headers: None,
+        method: req::Method::Post,
+    }
+}
+
+parse_noop!(clckru_parse);
+request!(clckru_req, req::Method::Get, "https://clck.ru/--?url={}");
+
+/// Parses the json response of the cutt.ly API, mapping the documented status
+/// codes to the errors.
+fn cuttly_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+    let json = json.get("url").ok_or(ProviderError::Deserialize)?;
+
+    match json.get("status").and_then(|s| s.as_u64()) {
+        Some(7) => json
+            .get("shortLink")
+            .and_then(|s| s.as_str())
+            .map(String::from)
+            .ok_or(ProviderError::Deserialize),
+        Some(1) => Err(cuttly_error(CuttlyError::AlreadyShortened)),
+        Some(2) => Err(cuttly_error(CuttlyError::NotALink)),
+        Some(3) => Err(cuttly_error(CuttlyError::AliasTaken)),
+        Some(4) => Err(cuttly_error(CuttlyError::InvalidApiKey)),
+        Some(5) => Err(cuttly_error(CuttlyError::InvalidCharacters)),
+        Some(6) => Err(cuttly_error(CuttlyError::BlockedDomain)),
+        Some(8) => Err(cuttly_error(CuttlyError::LimitReached)),
+        _ => Err(ProviderError::Deserialize),
+    }
+}
+fn cuttly_error(error: CuttlyError) -> ProviderError {
+    ProviderError::Provider(ProviderCode::Cuttly(error))
+}
+fn cuttly_req(url: &str, api_key: &str, options: &ShortenOptions) -> req::Request {
+    let mut query = encode::Serializer::new(String::new());
+    query.append_pair("key", api_key).append_pair("short", url);
+    if let Some(ref alias) = options.alias {
+        query.append_pair("name", alias);
+    }
+
+    req::Request {
+        url: format!("https://cutt.ly/api/api.php?{}", query.finish()),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+parse_json_tag!(fifocc_parse, "shortner", "http://fifo.cc/");
+request!(
+    fifocc_req,
+    req::Method::Get,
+    "https://fifo.cc/api/v2?url={}"
+);
+
+parse_noop!(gggg_parse);
+fn gggg_req(url: &str, options: &ShortenOptions) -> req::Request {
+    let mut body = encode::Serializer::new(String::new());
+    body.append_pair("long_url", url);
+    if let Some(ref alias) = options.alias {
+        body.append_pair("custom_path", alias);
+    }
+
+    req::Request {
+        url: "http://gg.gg/create".to_owned(),
+        body: Some(body.finish()),
+        content_type: Some(req::ContentType::FormUrlEncoded),
+        user_agent: None,
+        headers: None,
+        method: req::Method::Post,
+    }
+}
+
+fn googl_req(url: &str, key: &str) -> req::Request {
+    req::Request {
+        url: format!("https://www.googleapis.com/urlshortener/v1/url?key={}", key),
+        body: Some(format!(r#"{{"longUrl": "{}"}}"#, url)),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: None,
+        method: req::Method::Post,
+    }
+}
+
+parse_json_tag!(hmmrs_parse, "shortUrl", "");
+fn hmmrs_req(url: &str) -> req::Request {
+    req::Request {
+        url: "http:/hmm.rs/x/shorten".to_owned(),
+        body: Some(format!(r#"{{"url": "{}"}}"#, url)),
+        content_type: Some(req::ContentType::Json),
+        user_agent: Some(req::UserAgent(FAKE_USER_AGENT.to_owned())),
+        headers: None,
+        method: req::Method::Post,
+    }
+}
+
+parse_xml_tag!(hecsu_parse, "short");
+request!(
+    hecsu_req,
+    req::Method::Get,
+    "https://hec.su/api?url={}&method=xml"
+);
+
+/// Creates a request to the is.gd API compatible service (is.gd or v.gd).
+fn gd_req(base: &str, url: &str, options: &ShortenOptions) -> req::Request {
+    let mut query = encode::Serializer::new(String::new());
+    query.append_pair("format", "json").append_pair("url", url);
+    if let Some(ref alias) = options.alias {
+        query.append_pair("shorturl", alias);
+    }
+    if options.log_stats {
+        query.append_pair("logstats", "1");
+    }
+
+    req::Request {
+        url: format!("{}/create.php?{}", base, query.finish()),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+/// Parses the json response of the is.gd API compatible service, mapping the
+/// documented error codes to the errors.
+fn gd_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    if let Some(short_url) = json.get("shorturl").and_then(|s| s.as_str()) {
+        return Ok(short_url.to_owned());
+    }
+
+    let error = match json.get("errorcode").and_then(|c| c.as_u64()) {
+        Some(1) => IsGdError::InvalidUrl,
+        Some(2) => IsGdError::InvalidAlias,
+        Some(3) => IsGdError::RateLimited,
+        Some(4) => IsGdError::Other,
+        _ => return Err(ProviderError::Deserialize),
+    };
+    Err(ProviderError::Provider(ProviderCode::IsGd(error)))
+}
+
+/// Parses the total hit count out of the statistics page of the is.gd API
+/// compatible service, which provides no statistics API.
+fn gd_stats_parse(res: &str) -> Option<LinkStats> {
+    let mut text = String::with_capacity(res.len());
+    let mut in_tag = false;
+    for c in res.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let clicks = text
+        .split("Total")
+        .nth(1)?
+        .split(|c: char| !c.is_ascii_digit() && c != ',')
+        .map(|n| n.replace(',', ""))
+        .find(|n| !n.is_empty())?
+        .parse()
+        .ok()?;
+    Some(LinkStats { clicks })
+}
+fn gd_stats_req(base: &str, short_url: &str) -> req::Request {
+    let code = short_url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or("");
+    let code = encode::byte_serialize(code.as_bytes());
+
+    req::Request {
+        url: format!("{}/stats.php?url={}", base, code),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+fn isgd_req(url: &str, options: &ShortenOptions) -> req::Request {
+    gd_req("https://is.gd", url, options)
+}
+
+fn kutt_headers(api_key: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("X-API-Key", secret_header(api_key));
+    headers
+}
+
+/// Parses the json response of the kutt.it API, mapping the error messages
+/// to the errors. The API reports no error codes, only messages.
+fn kutt_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    if let Some(link) = json.get("link").and_then(|l| l.as_str()) {
+        return Ok(link.to_owned());
+    }
+
+    let message = json
+        .get("error")
+        .and_then(|e| e.as_str())
+        .ok_or(ProviderError::Deserialize)?
+        .to_lowercase();
+    let error = if message.contains("custom url") && message.contains("in use") {
+        KuttError::AliasTaken
+    } else if message.contains("api key") {
+        KuttError::InvalidApiKey
+    } else if message.contains("limit") {
+        KuttError::LimitReached
+    } else if message.contains("url") {
+        KuttError::InvalidUrl
+    } else {
+        return Err(ProviderError::Deserialize);
+    };
+    Err(ProviderError::Provider(ProviderCode::Kutt(error)))
+}
+fn kutt_req(
+    url: &str,
+    api_key: &str,
+    host: Option<&str>,
+    options: &ShortenOptions,
+) -> req::Request {
+    let mut body = serde_json::json!({ "target": url });
+    if let Some(ref alias) = options.alias {
+        body["customurl"] = alias.as_str().into();
+    }
+    if let Some(ref expires) = options.expires {
+        let seconds = expires.duration_from_now().as_secs();
+        body["expire_in"] = format!("{} seconds", seconds).into();
+    }
+    if let Some(ref password) = options.password {
+        body["password"] = password.as_str().into();
+    }
+    if let Some(ref description) = options.description {
+        body["description"] = description.as_str().into();
+    }
+    if let Some(ref domain) = options.domain {
+        body["domain"] = domain.as_str().into();
+    }
+    if options.reuse_existing {
+        body["reuse"] = true.into();
+    }
+
+    req::Request {
+        url: format!("{}/api/v2/links", host.unwrap_or("https://kutt.it")),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(kutt_headers(api_key)),
+        method: req::Method::Post,
+    }
+}
+
+fn kutt_link(json: &serde_json::Value) -> Option<Link> {
+    Some(Link {
+        id: json.get("id")?.as_str()?.to_owned(),
+        short_url: json.get("link")?.as_str()?.to_owned(),
+        target: json.get("target")?.as_str()?.to_owned(),
+        description: json
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(String::from),
+        visits: json
+            .get("visit_count")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        created_at: json
+            .get("created_at")
+            .and_then(|c| c.as_str())
+            .map(String::from),
+        updated_at: json
+            .get("updated_at")
+            .and_then(|u| u.as_str())
+            .map(String::from),
+    })
+}
+fn kutt_list_links_parse(res: &str) -> Option<LinkPage> {
+    let json: serde_json::Value = serde_json::from_str(res).ok()?;
+    let links = json
+        .get("data")?
+        .as_array()?
+        .iter()
+        .map(kutt_link)
+        .collect::<Option<Vec<_>>>()?;
+    let total = json.get("total").and_then(|t| t.as_u64());
+    Some(LinkPage { links, total })
+}
+fn kutt_list_links_req(api_key: &str, host: Option<&str>, skip: u64, limit: u64) -> req::Request {
+    req::Request {
+        url: format!(
+            "{}/api/v2/links?skip={}&limit={}",
+            host.unwrap_or("https://kutt.it"),
+            skip,
+            limit
+        ),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: Some(kutt_headers(api_key)),
+        method: req::Method::Get,
+    }
+}
+
+fn kutt_delete_parse(res: &str) -> Option<()> {
+    let json: serde_json::Value = serde_json::from_str(res).ok()?;
+    match json.get("error") {
+        Some(_) => None,
+        None => json.get("message").map(|_| ()),
+    }
+}
+fn kutt_delete_req(id: &str, api_key: &str, host: Option<&str>) -> req::Request {
+    let id = encode::byte_serialize(id.as_bytes());
+
+    req::Request {
+        url: format!("{}/api/v2/links/{}", host.unwrap_or("https://kutt.it"), id),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: Some(kutt_headers(api_key)),
+        method: req::Method::Delete,
+    }
+}
+
+parse_json_tag!(lihicc_parse, "short_url", "");
+fn lihicc_req(
+    url: &str,
+    token: &str,
+    split_urls: &[String],
+    options: &ShortenOptions,
+) -> req::Request {
+    let mut body = serde_json::json!({ "url": url });
+    if !split_urls.is_empty() {
+        body["split_urls"] = split_urls.into();
+    }
+    if let Some(ref alias) = options.alias {
+        body["slug"] = alias.as_str().into();
+    }
+
+    req::Request {
+        url: "https://api.lihi.cc/v1/links".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(token)),
+        method: req::Method::Post,
+    }
+}
+
+parse_json_tag!(lnnkin_parse, "short_url", "");
+fn lnnkin_req(url: &str, options: &ShortenOptions) -> req::Request {
+    let mut query = encode::Serializer::new(String::new());
+    query.append_pair("url", url);
+    if let Some(ref alias) = options.alias {
+        query.append_pair("label", alias);
+    }
+
+    req::Request {
+        url: format!("https://lnnk.in/api?{}", query.finish()),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+fn mgnetme_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    match json.get("state").and_then(|s| s.as_str()) {
+        Some("success") => json
+            .get("shorturl")
+            .and_then(|s| s.as_str())
+            .map(String::from)
+            .ok_or(ProviderError::Deserialize),
+        Some(_) => Err(ProviderError::InvalidUrl),
+        None => Err(ProviderError::Deserialize),
+    }
+}
+request!(
+    mgnetme_req,
+    req::Method::Get,
+    "http://mgnet.me/api/create?format=json&m={}"
+);
+
+parse_json_tag!(n9cl_parse, "short_url", "");
+fn n9cl_req(url: &str, api_key: Option<&str>) -> req::Request {
+    req::Request {
+        url: "https://n9.cl/api/v1/shorten".to_owned(),
+        body: Some(serde_json::json!({ "url": url }).to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: api_key.map(bearer_headers),
+        method: req::Method::Post,
+    }
+}
+
+parse_noop!(nowlinks_parse);
+request!(
+    nowlinks_req,
+    req::Method::Get,
+    "http://nowlinks.net/api?url={}"
+);
+
+parse_noop!(onelink_parse);
+fn onelink_req(
+    url: &str,
+    template_id: &str,
+    api_token: &str,
+    options: &ShortenOptions,
+) -> req::Request {
+    let mut headers = HeaderMap::new();
+    headers.insert("authorization", secret_header(api_token));
+
+    let mut data = serde_json::json!({ "af_web_dp": url });
+    if let Some(ref title) = options.title {
+        data["af_og_title"] = title.as_str().into();
+    }
+    if let Some(ref description) = options.description {
+        data["af_og_description"] = description.as_str().into();
+    }
+    if let Some(ref deep_link) = options.deep_link {
+        if let Some(ref ios_url) = deep_link.ios_url {
+            data["af_ios_url"] = ios_url.as_str().into();
+        }
+        if let Some(android_url) = deep_link.android_fallback() {
+            data["af_android_url"] = android_url.into();
+        }
+        if let Some(ref campaign) = deep_link.campaign {
+            data["c"] = campaign.as_str().into();
+        }
+        if let Some(ref channel) = deep_link.channel {
+            data["af_channel"] = channel.as_str().into();
+        }
+        for (key, value) in &deep_link.data {
+            data[key] = value.as_str().into();
+        }
+    }
+
+    let mut body = serde_json::json!({ "data": data });
+    if let Some(ref alias) = options.alias {
+        body["shortlink_id"] = alias.as_str().into();
+    }
+
+    req::Request {
+        url: format!("https://onelink.appsflyer.com/shortlink/v1/{}", template_id),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(headers),
+        method: req::Method::Post,
+    }
+}
+
+parse_noop!(ouoio_parse);
+fn ouoio_req(url: &str, api_key: &str) -> req::Request {
+    let query = encode::Serializer::new(String::new())
+        .append_pair("s", url)
+        .finish();
+
+    req::Request {
+        url: format!("https://ouo.io/api/{}?{}", api_key, query),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+parse_noop!(phxcoin_parse);
+request!(
+    phxcoin_req,
+    req::Method::Get,
+    "http://phx.co.in/shrink.asp?url={}"
+);
+
+parse_xml_tag!(psbeco_parse, "ShortUrl");
+request!(
+    psbeco_req,
+    req::Method::Get,
+    "http://psbe.co/API.asmx/CreateUrl?real_url={}"
+);
+
+fn pseis_parse(res: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(res).ok()?;
+    json.get("data")?
+        .get("picseeUrl")?
+        .as_str()
+        .map(String::from)
+}
+fn pseis_req(url: &str, token: &str, options: &ShortenOptions) -> req::Request {
+    let mut body = serde_json::json!({ "url": url });
+    if let Some(ref alias) = options.alias {
+        body["path"] = alias.as_str().into();
+    }
+    // The title and description override the ones of the target page in the
+    // link previews.
+    if let Some(ref title) = options.title {
+        body["title"] = title.as_str().into();
+    }
+    if let Some(ref description) = options.description {
+        body["description"] = description.as_str().into();
+    }
+
+    let query = encode::Serializer::new(String::new())
+        .append_pair("access_token", token)
+        .finish();
+
+    req::Request {
+        url: format!("https://api.pics.ee/v1/links/?{}", query),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: None,
+        method: req::Method::Post,
+    }
+}
+
+fn spoome_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    if let Some(short_url) = json.get("short_url").and_then(|s| s.as_str()) {
+        Ok(short_url.to_owned())
+    } else if json.get("AliasError").is_some() {
+        Err(ProviderError::AliasTaken)
+    } else if json.get("UrlError").is_some() {
+        Err(ProviderError::InvalidUrl)
+    } else {
+        Err(ProviderError::Deserialize)
+    }
+}
+fn spoome_req(url: &str, options: &ShortenOptions) -> req::Request {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static(req::CONTENT_JSON));
+
+    let mut body = encode::Serializer::new(String::new());
+    body.append_pair("url", url);
+    if let Some(ref alias) = options.alias {
+        body.append_pair("alias", alias);
+    }
+    if let Some(ref password) = options.password {
+        body.append_pair("password", password);
+    }
+    if let Some(max_clicks) = options.max_clicks {
+        body.append_pair("max-clicks", &max_clicks.to_string());
+    }
+
+    req::Request {
+        url: "https://spoo.me/".to_owned(),
+        body: Some(body.finish()),
+        content_type: Some(req::ContentType::FormUrlEncoded),
+        user_agent: None,
+        headers: Some(headers),
+        method: req::Method::Post,
+    }
+}
+
+parse_noop!(scoop_parse);
+request!(
     scoop_req,
     req::Method::Get,
     "http://s.coop/devapi.php?action=shorturl&url={}&format=RETURN"
 );
 
+// The short URL is reported without a scheme.
+parse_json_tag!(rebrandly_parse, "shortUrl", "https://");
+fn rebrandly_req(
+    url: &str,
+    api_key: &str,
+    domain: Option<&str>,
+    options: &ShortenOptions,
+) -> req::Request {
+    let mut headers = HeaderMap::new();
+    headers.insert("apikey", secret_header(api_key));
+
+    let mut body = serde_json::json!({ "destination": url });
+    if let Some(domain) = options.domain.as_deref().or(domain) {
+        body["domain"] = serde_json::json!({ "fullName": domain });
+    }
+    if let Some(ref alias) = options.alias {
+        body["slashtag"] = alias.as_str().into();
+    }
+    if let Some(ref title) = options.title {
+        body["title"] = title.as_str().into();
+    }
+
+    req::Request {
+        url: "https://api.rebrandly.com/v1/links".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(headers),
+        method: req::Method::Post,
+    }
+}
+
 parse_noop!(rlu_parse);
 request!(
     rlu_req,
@@ -411,6 +2734,158 @@ request!(
     "http://rlu.ru/index.sema?a=api&link={}"
 );
 
+parse_json_tag!(shortio_parse, "shortURL", "");
+fn shortio_req(url: &str, api_key: &str, domain: &str, options: &ShortenOptions) -> req::Request {
+    let mut body = shortio_link(url, options);
+    body["domain"] = domain.into();
+    if let Some(ref alias) = options.alias {
+        body["path"] = alias.as_str().into();
+    }
+
+    req::Request {
+        url: "https://api.short.io/links".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(shortio_headers(api_key)),
+        method: req::Method::Post,
+    }
+}
+
+// The aliases are left out, as a single alias can not be shared by the links.
+fn shortio_bulk_req(
+    urls: &[&str],
+    api_key: &str,
+    domain: &str,
+    options: &ShortenOptions,
+) -> req::Request {
+    let links = urls
+        .iter()
+        .map(|url| shortio_link(url, options))
+        .collect::<Vec<_>>();
+    let body = serde_json::json!({ "domain": domain, "links": links });
+
+    req::Request {
+        url: "https://api.short.io/links/bulk".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(shortio_headers(api_key)),
+        method: req::Method::Post,
+    }
+}
+
+// The links are reported in the order of the request, the failed ones as
+// error objects.
+fn shortio_bulk_parse(res: &str) -> Option<Vec<Result<String, ProviderError>>> {
+    let json: serde_json::Value = serde_json::from_str(res).ok()?;
+    let links = json
+        .as_array()?
+        .iter()
+        .map(|link| {
+            link.get("shortURL")
+                .and_then(|s| s.as_str())
+                .map(String::from)
+                .ok_or(ProviderError::InvalidUrl)
+        })
+        .collect();
+    Some(links)
+}
+
+fn shortio_headers(api_key: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::AUTHORIZATION, secret_header(api_key));
+    headers
+}
+
+/// Creates the json object describing a link for the short.io API.
+fn shortio_link(url: &str, options: &ShortenOptions) -> serde_json::Value {
+    let mut link = serde_json::json!({ "originalURL": url });
+    if let Some(ref title) = options.title {
+        link["title"] = title.as_str().into();
+    }
+    if !options.tags.is_empty() {
+        link["tags"] = options.tags.clone().into();
+    }
+    if let Some(max_clicks) = options.max_clicks {
+        link["clicksLimit"] = max_clicks.into();
+    }
+    if options.private {
+        link["hidden"] = true.into();
+    }
+    // The existing link is returned unless duplicates are allowed.
+    if options.reuse_existing {
+        link["allowDuplicates"] = false.into();
+    }
+    if let Some(ref password) = options.password {
+        link["password"] = password.as_str().into();
+    }
+    if let Some(ref expires) = options.expires {
+        let millis = expires
+            .time()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        link["expiresAt"] = millis.into();
+    }
+    link
+}
+
+parse_json_tag!(shortest_parse, "shortenedUrl", "");
+fn shortest_req(url: &str, api_token: &str) -> req::Request {
+    let mut headers = HeaderMap::new();
+    headers.insert("public-api-token", secret_header(api_token));
+
+    let body = encode::Serializer::new(String::new())
+        .append_pair("urlToShorten", url)
+        .finish();
+
+    req::Request {
+        url: "https://api.shorte.st/v1/data/url".to_owned(),
+        body: Some(body),
+        content_type: Some(req::ContentType::FormUrlEncoded),
+        user_agent: None,
+        headers: Some(headers),
+        method: req::Method::Put,
+    }
+}
+
+fn shrtcode_parse(res: &str, options: &ShortenOptions) -> Result<String, ProviderError> {
+    let links = parse_shrtcode_links(res)?;
+    Ok(match options.domain.as_deref() {
+        Some("9qr.de") => links.nine_qr_de,
+        Some("shiny.link") => links.shiny_link,
+        _ => links.shrtco_de,
+    })
+}
+request!(
+    shrtcode_req,
+    req::Method::Get,
+    "https://api.shrtco.de/v2/shorten?url={}"
+);
+
+// Only the slug of the short URL is reported.
+parse_json_tag!(sid_parse, "short", "https://s.id/");
+fn sid_req(url: &str, client_id: &str, api_key: &str, options: &ShortenOptions) -> req::Request {
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Auth-Id", secret_header(client_id));
+    headers.insert("X-Auth-Key", secret_header(api_key));
+
+    let mut body = serde_json::json!({ "long_url": url });
+    if let Some(ref alias) = options.alias {
+        body["short"] = alias.as_str().into();
+    }
+
+    req::Request {
+        url: "https://api.s.id/v1/links".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(headers),
+        method: req::Method::Post,
+    }
+}
+
 parse_json_tag!(sirbz_parse, "short_link", "");
 request!(
     sirbz_req,
@@ -420,6 +2895,42 @@ request!(
     req::ContentType::FormUrlEncoded
 );
 
+parse_json_tag!(sniply_parse, "href", "");
+fn sniply_req(url: &str, token: &str, cta: Option<&str>, campaign: Option<&str>) -> req::Request {
+    let mut body = serde_json::json!({ "url": url });
+    if let Some(cta) = cta {
+        body["cta"] = cta.into();
+    }
+    if let Some(campaign) = campaign {
+        body["campaign"] = campaign.into();
+    }
+
+    req::Request {
+        url: "https://snip.ly/api/v1/snips/".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(token)),
+        method: req::Method::Post,
+    }
+}
+
+fn t2m_req(url: &str, api_key: &str, options: &ShortenOptions) -> req::Request {
+    let mut body = serde_json::json!({ "long_url": url, "qr_code": true });
+    if let Some(ref alias) = options.alias {
+        body["alias"] = alias.as_str().into();
+    }
+
+    req::Request {
+        url: "https://t2m.io/api/v1/create".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(api_key)),
+        method: req::Method::Post,
+    }
+}
+
 fn tinyurl_parse(res: &str) -> Option<String> {
     res.split("data-clipboard-text=\"")
         .nth(1)
@@ -434,6 +2945,29 @@ request!(
     "http://tinyurl.com/create.php?url={}"
 );
 
+parse_json_tag!(tinyurlapi_parse, "tiny_url", "");
+fn tinyurlapi_req(url: &str, token: &str, options: &ShortenOptions) -> req::Request {
+    let mut body = serde_json::json!({ "url": url });
+    if let Some(ref alias) = options.alias {
+        body["alias"] = alias.as_str().into();
+    }
+    if let Some(ref domain) = options.domain {
+        body["domain"] = domain.as_str().into();
+    }
+    if let Some(ref expires) = options.expires {
+        body["expires_at"] = expires.to_utc_string().into();
+    }
+
+    req::Request {
+        url: "https://api.tinyurl.com/create".to_owned(),
+        body: Some(body.to_string()),
+        content_type: Some(req::ContentType::Json),
+        user_agent: None,
+        headers: Some(bearer_headers(token)),
+        method: req::Method::Post,
+    }
+}
+
 parse_json_tag!(tinyph_parse, "hash", "http://tiny.ph/");
 request!(
     tinyph_req,
@@ -443,12 +2977,87 @@ request!(
     req::ContentType::FormUrlEncoded
 );
 
-parse_xml_tag!(tnyim_parse, "shorturl");
-request!(
-    tnyim_req,
-    req::Method::Get,
-    "http://tny.im/yourls-api.php?action=shorturl&url={}"
-);
+/// Parses the json response of the YOURLS API, mapping the documented error
+/// codes to the errors.
+fn yourls_parse(res: &str) -> Result<String, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    // The short URL is reported even when the URL has already been shortened.
+    if let Some(short_url) = json.get("shorturl").and_then(|s| s.as_str()) {
+        return Ok(short_url.to_owned());
+    }
+
+    match json.get("code").and_then(|c| c.as_str()) {
+        Some("error:keyword") => Err(ProviderError::AliasTaken),
+        Some("error:nourl") | Some("error:url") => Err(ProviderError::InvalidUrl),
+        _ => Err(ProviderError::Deserialize),
+    }
+}
+fn yourls_stats_parse(res: &str) -> Option<LinkStats> {
+    let json: serde_json::Value = serde_json::from_str(res).ok()?;
+    // YOURLS reports the number of clicks as a string.
+    let clicks = json.get("link")?.get("clicks")?;
+    let clicks = match clicks.as_u64() {
+        Some(clicks) => clicks,
+        None => clicks.as_str()?.parse().ok()?,
+    };
+    Some(LinkStats { clicks })
+}
+/// Creates a request to the YOURLS API with the given action parameters.
+fn yourls_req(base_url: &str, auth: &YourlsAuth, params: &[(&str, &str)]) -> req::Request {
+    let mut query = encode::Serializer::new(String::new());
+    query.extend_pairs(params).append_pair("format", "json");
+    match *auth {
+        YourlsAuth::Public => {}
+        YourlsAuth::Signature(ref signature) => {
+            query.append_pair("signature", signature);
+        }
+        YourlsAuth::Password {
+            ref username,
+            ref password,
+        } => {
+            query
+                .append_pair("username", username)
+                .append_pair("password", password);
+        }
+    }
+
+    req::Request {
+        url: format!(
+            "{}/yourls-api.php?{}",
+            base_url.trim_end_matches('/'),
+            query.finish()
+        ),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+fn yourls_shorten_req(
+    base_url: &str,
+    auth: &YourlsAuth,
+    url: &str,
+    options: &ShortenOptions,
+) -> req::Request {
+    let mut params = vec![("action", "shorturl"), ("url", url)];
+    if let Some(ref alias) = options.alias {
+        params.push(("keyword", alias));
+    }
+    if let Some(ref title) = options.title {
+        params.push(("title", title));
+    }
+    yourls_req(base_url, auth, &params)
+}
+fn yourls_stats_req(base_url: &str, auth: &YourlsAuth, short_url: &str) -> req::Request {
+    yourls_req(
+        base_url,
+        auth,
+        &[("action", "url-stats"), ("shorturl", short_url)],
+    )
+}
 
 parse_noop!(urlshortenerio_parse);
 request!(
@@ -459,11 +3068,22 @@ request!(
     req::ContentType::FormUrlEncoded
 );
 
-parse_noop!(vgd_parse);
+fn vgd_req(url: &str, options: &ShortenOptions) -> req::Request {
+    gd_req("http://v.gd", url, options)
+}
+
+parse_noop!(vht_parse);
+request!(
+    vht_req,
+    req::Method::Get,
+    "https://v.ht/processreq.php?url={}"
+);
+
+parse_noop!(vurlcom_parse);
 request!(
-    vgd_req,
+    vurlcom_req,
     req::Method::Get,
-    "http://v.gd/create.php?format=simple&url={}"
+    "https://vurl.com/api.php?url={}"
 );
 
 parse_json_tag!(biturl_parse, "short", "");
@@ -475,34 +3095,349 @@ request!(
     req::ContentType::FormUrlEncoded
 );
 
-/// Parses the response from a successful request to a provider into the
-/// URL-shortened string.
-pub fn parse(res: &str, provider: &Provider) -> Result<String, ProviderError> {
-    match *provider {
-        Provider::Abv8 => abv8_parse(res),
-        Provider::BamBz => bambz_parse(res),
-        Provider::BitLy { .. } => bitly_parse(res),
-        Provider::BitUrl => biturl_parse(res),
-        Provider::Bmeo => bmeo_parse(res),
-        Provider::FifoCc => fifocc_parse(res),
-        Provider::GooGl { .. } => googl_parse(res),
-        Provider::HmmRs => hmmrs_parse(res),
-        Provider::HecSu => hecsu_parse(res),
-        Provider::IsGd => isgd_parse(res),
-        Provider::Kutt { .. } => kutt_parse(res),
-        Provider::NowLinks => nowlinks_parse(res),
-        Provider::PhxCoIn => phxcoin_parse(res),
-        Provider::PsbeCo => psbeco_parse(res),
-        Provider::SCoop => scoop_parse(res),
-        Provider::SirBz => sirbz_parse(res),
-        Provider::Rlu => rlu_parse(res),
-        Provider::TinyUrl => tinyurl_parse(res),
-        Provider::TinyPh => tinyph_parse(res),
-        Provider::TnyIm => tnyim_parse(res),
-        Provider::UrlShortenerIo => urlshortenerio_parse(res),
-        Provider::VGd => vgd_parse(res),
+/// Parses the response from a successful request to a provider into the
+/// URL-shortened string.
+///
+/// Returns `ProviderError::Unsupported` for `Provider::Local` and
+/// `Provider::Fake`, which send no request.
+pub fn parse(res: &str, provider: &Provider) -> Result<String, ProviderError> {
+    parse_with_options(res, provider, &ShortenOptions::default())
+}
+
+/// Parses the response from a successful request to a provider made with the
+/// additional link options into the URL-shortened string.
+#[allow(deprecated)]
+pub fn parse_with_options(
+    res: &str,
+    provider: &Provider,
+    options: &ShortenOptions,
+) -> Result<String, ProviderError> {
+    match *provider {
+        Provider::Abv8 => abv8_parse(res),
+        Provider::AdfLy { .. } => adfly_parse(res),
+        Provider::BamBz => bambz_parse(res),
+        Provider::BitLy { .. } => return bitly_parse(res),
+        Provider::BitUrl => biturl_parse(res),
+        Provider::Blink { .. } => blink_parse(res),
+        Provider::Bmeo => bmeo_parse(res),
+        Provider::Branch { .. } => branch_parse(res),
+        Provider::ChhotoUrl { .. } => return chhotourl_parse(res),
+        Provider::CleanUri => cleanuri_parse(res),
+        Provider::ClckRu => clckru_parse(res),
+        Provider::Cuttly { .. } => return cuttly_parse(res),
+        Provider::Fake { .. } => return Err(ProviderError::Unsupported),
+        Provider::FifoCc => fifocc_parse(res),
+        Provider::GgGg => gggg_parse(res),
+        Provider::GooGl { .. } => return Err(ProviderError::Discontinued),
+        Provider::HmmRs => hmmrs_parse(res),
+        Provider::HecSu => hecsu_parse(res),
+        Provider::IsGd => return gd_parse(res),
+        Provider::Kutt { .. } => return kutt_parse(res),
+        Provider::LihiCc { .. } => lihicc_parse(res),
+        Provider::LnnkIn => lnnkin_parse(res),
+        Provider::Local { .. } => return Err(ProviderError::Unsupported),
+        Provider::MgnetMe => return mgnetme_parse(res),
+        Provider::N9Cl { .. } => n9cl_parse(res),
+        Provider::NowLinks => nowlinks_parse(res),
+        Provider::OneLink { .. } => onelink_parse(res),
+        Provider::OuoIo { .. } => ouoio_parse(res),
+        Provider::PhxCoIn => phxcoin_parse(res),
+        Provider::PsbeCo => psbeco_parse(res),
+        Provider::PseIs { .. } => pseis_parse(res),
+        Provider::SnipLy { .. } => sniply_parse(res),
+        Provider::SpooMe => return spoome_parse(res),
+        Provider::SCoop => scoop_parse(res),
+        Provider::ShortIo { .. } => shortio_parse(res),
+        Provider::ShorteSt { .. } => shortest_parse(res),
+        Provider::ShrtcoDe => return shrtcode_parse(res, options),
+        Provider::SId { .. } => sid_parse(res),
+        Provider::SirBz => sirbz_parse(res),
+        Provider::Rebrandly { .. } => rebrandly_parse(res),
+        Provider::Rlu => rlu_parse(res),
+        Provider::T2m { .. } => return parse_t2m_link(res).map(|link| link.short_url),
+        Provider::TinyUrl => tinyurl_parse(res),
+        Provider::TinyUrlApi { .. } => tinyurlapi_parse(res),
+        Provider::TinyPh => tinyph_parse(res),
+        Provider::TnyIm | Provider::Yourls { .. } => return yourls_parse(res),
+        Provider::UrlShortenerIo => urlshortenerio_parse(res),
+        Provider::VGd => return gd_parse(res),
+        Provider::VHt => vht_parse(res),
+        Provider::VurlCom => vurlcom_parse(res),
+    }
+    .ok_or(ProviderError::Deserialize)
+}
+
+/// Declares a module with the parser of the responses of a provider, for
+/// executing the requests with another http client.
+macro_rules! parser {
+    ($module:ident, $name:expr, $res:ident => $parse:expr) => {
+        #[doc = concat!("The parser of the responses of ", $name, ".")]
+        pub mod $module {
+            use super::*;
+
+            /// Parses the response from a successful request to the provider
+            /// into the short URL, like `providers::parse`.
+            pub fn parse_response($res: &str) -> Result<String, ProviderError> {
+                $parse
+            }
+        }
+    };
+}
+
+parser!(abv8, "abv8.me", res => abv8_parse(res).ok_or(ProviderError::Deserialize));
+parser!(adfly, "adf.ly", res => adfly_parse(res).ok_or(ProviderError::Deserialize));
+parser!(bambz, "bam.bz", res => bambz_parse(res).ok_or(ProviderError::Deserialize));
+parser!(bitly, "bit.ly", res => bitly_parse(res));
+parser!(biturl, "biturl.top", res => biturl_parse(res).ok_or(ProviderError::Deserialize));
+parser!(blink, "bl.ink", res => blink_parse(res).ok_or(ProviderError::Deserialize));
+parser!(bmeo, "bmeo.org", res => bmeo_parse(res).ok_or(ProviderError::Deserialize));
+parser!(branch, "app.link", res => branch_parse(res).ok_or(ProviderError::Deserialize));
+parser!(chhotourl, "Chhoto URL", res => chhotourl_parse(res));
+parser!(cleanuri, "cleanuri.com", res => cleanuri_parse(res).ok_or(ProviderError::Deserialize));
+parser!(clckru, "clck.ru", res => clckru_parse(res).ok_or(ProviderError::Deserialize));
+parser!(cuttly, "cutt.ly", res => cuttly_parse(res));
+parser!(fifocc, "fifo.cc", res => fifocc_parse(res).ok_or(ProviderError::Deserialize));
+parser!(gggg, "gg.gg", res => gggg_parse(res).ok_or(ProviderError::Deserialize));
+parser!(hmmrs, "hmm.rs", res => hmmrs_parse(res).ok_or(ProviderError::Deserialize));
+parser!(hecsu, "hec.su", res => hecsu_parse(res).ok_or(ProviderError::Deserialize));
+parser!(isgd, "is.gd", res => gd_parse(res));
+parser!(kutt, "Kutt", res => kutt_parse(res));
+parser!(lihicc, "lihi.cc", res => lihicc_parse(res).ok_or(ProviderError::Deserialize));
+parser!(lnnkin, "lnnk.in", res => lnnkin_parse(res).ok_or(ProviderError::Deserialize));
+parser!(mgnetme, "mgnet.me", res => mgnetme_parse(res));
+parser!(n9cl, "n9.cl", res => n9cl_parse(res).ok_or(ProviderError::Deserialize));
+parser!(nowlinks, "nowlinks.net", res => nowlinks_parse(res).ok_or(ProviderError::Deserialize));
+parser!(onelink, "onelink.me", res => onelink_parse(res).ok_or(ProviderError::Deserialize));
+parser!(ouoio, "ouo.io", res => ouoio_parse(res).ok_or(ProviderError::Deserialize));
+parser!(phxcoin, "phx.co.in", res => phxcoin_parse(res).ok_or(ProviderError::Deserialize));
+parser!(psbeco, "psbe.co", res => psbeco_parse(res).ok_or(ProviderError::Deserialize));
+parser!(pseis, "pse.is", res => pseis_parse(res).ok_or(ProviderError::Deserialize));
+parser!(sniply, "snip.ly", res => sniply_parse(res).ok_or(ProviderError::Deserialize));
+parser!(spoome, "spoo.me", res => spoome_parse(res));
+parser!(scoop, "s.coop", res => scoop_parse(res).ok_or(ProviderError::Deserialize));
+parser!(shortio, "short.io", res => shortio_parse(res).ok_or(ProviderError::Deserialize));
+parser!(shortest, "shorte.st", res => shortest_parse(res).ok_or(ProviderError::Deserialize));
+parser!(shrtcode, "shrtco.de", res => shrtcode_parse(res, &ShortenOptions::default()));
+parser!(sid, "s.id", res => sid_parse(res).ok_or(ProviderError::Deserialize));
+parser!(sirbz, "sirbz.com", res => sirbz_parse(res).ok_or(ProviderError::Deserialize));
+parser!(rebrandly, "Rebrandly", res => rebrandly_parse(res).ok_or(ProviderError::Deserialize));
+parser!(rlu, "rlu.ru", res => rlu_parse(res).ok_or(ProviderError::Deserialize));
+parser!(t2m, "t2m.io", res => parse_t2m_link(res).map(|link| link.short_url));
+parser!(tinyurl, "tinyurl.com", res => tinyurl_parse(res).ok_or(ProviderError::Deserialize));
+parser!(tinyurlapi, "the TinyURL API", res => tinyurlapi_parse(res).ok_or(ProviderError::Deserialize));
+parser!(tinyph, "tiny.ph", res => tinyph_parse(res).ok_or(ProviderError::Deserialize));
+parser!(tnyim, "tny.im", res => yourls_parse(res));
+parser!(urlshortenerio, "url-shortener.io", res => urlshortenerio_parse(res).ok_or(ProviderError::Deserialize));
+parser!(vgd, "v.gd", res => gd_parse(res));
+parser!(vht, "v.ht", res => vht_parse(res).ok_or(ProviderError::Deserialize));
+parser!(vurlcom, "vurl.com", res => vurlcom_parse(res).ok_or(ProviderError::Deserialize));
+parser!(yourls, "YOURLS", res => yourls_parse(res));
+
+/// Parses the response from a successful request to shrtco.de into the short
+/// URLs on all of its domains.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::providers;
+///
+/// let res = r#"{"ok":true,"result":{"code":"KCveN",
+///     "full_short_link":"https://shrtco.de/KCveN",
+///     "full_short_link2":"https://9qr.de/KCveN",
+///     "full_short_link3":"https://shiny.link/KCveN"}}"#;
+/// let links = providers::parse_shrtcode_links(res).unwrap();
+/// assert_eq!(links.nine_qr_de, "https://9qr.de/KCveN");
+/// ```
+pub fn parse_shrtcode_links(res: &str) -> Result<ShrtcoDeLinks, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+
+    if let Some(result) = json.get("result") {
+        let link = |name: &str| {
+            result
+                .get(name)
+                .and_then(|l| l.as_str())
+                .map(String::from)
+                .ok_or(ProviderError::Deserialize)
+        };
+        return Ok(ShrtcoDeLinks {
+            shrtco_de: link("full_short_link")?,
+            nine_qr_de: link("full_short_link2")?,
+            shiny_link: link("full_short_link3")?,
+        });
+    }
+
+    match json.get("error_code").and_then(|c| c.as_u64()) {
+        // 1: no url, 2: invalid url, 10: disallowed link.
+        Some(1) | Some(2) | Some(10) => Err(ProviderError::InvalidUrl),
+        Some(3) => Err(ProviderError::RateLimited),
+        Some(4) => Err(ProviderError::Unauthorized),
+        Some(5) => Err(ProviderError::AliasTaken),
+        Some(6) => Err(ProviderError::Unavailable),
+        _ => Err(ProviderError::Deserialize),
+    }
+}
+
+/// Parses the response from a successful request to t2m.io into the short URL
+/// and its QR code.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::providers;
+///
+/// let res = r#"{"data":{"short_url":"https://t2m.io/aBcD",
+///     "qr_code":"https://t2m.io/qr/aBcD.png"}}"#;
+/// let link = providers::parse_t2m_link(res).unwrap();
+/// assert_eq!(link.short_url, "https://t2m.io/aBcD");
+/// ```
+pub fn parse_t2m_link(res: &str) -> Result<T2mLink, ProviderError> {
+    let json: serde_json::Value =
+        serde_json::from_str(res).map_err(|_| ProviderError::Deserialize)?;
+    let data = json.get("data").ok_or(ProviderError::Deserialize)?;
+    let field = |name: &str| data.get(name).and_then(|v| v.as_str()).map(String::from);
+
+    Ok(T2mLink {
+        short_url: field("short_url").ok_or(ProviderError::Deserialize)?,
+        qr_code: field("qr_code"),
+    })
+}
+
+/// Returns whether the URL is on the domain of a known URL shortener service,
+/// or on one of its subdomains. The self hosted instances and the branded
+/// domains are not known.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::providers;
+///
+/// assert!(providers::is_short_url("https://bit.ly/2Ewn0ZT"));
+/// assert!(providers::is_short_url("https://example.app.link/a1"));
+/// assert!(!providers::is_short_url("https://www.rust-lang.org/learn"));
+/// assert!(!providers::is_short_url("not a url"));
+/// ```
+pub fn is_short_url(url: &str) -> bool {
+    match host_of(url) {
+        Some(host) => domain_suffixes(&host).any(|d| KNOWN_DOMAINS.binary_search(&d).is_ok()),
+        None => false,
+    }
+}
+
+/// Returns the domains of the known URL shortener services, the built-in
+/// providers included, in alphabetical order.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::providers;
+///
+/// assert!(providers::known_domains().contains(&"is.gd"));
+/// ```
+pub fn known_domains() -> &'static [&'static str] {
+    KNOWN_DOMAINS
+}
+
+/// Matches the URLs against a set of URL shortener domains: the known ones
+/// (see `known_domains`) and the ones added, like the branded domains or the
+/// self hosted instances.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::providers::DomainMatcher;
+///
+/// let matcher = DomainMatcher::new().with_domains(["go.example.com"]);
+/// assert!(matcher.is_short_url("https://go.example.com/a1"));
+/// assert!(matcher.is_short_url("https://is.gd/a1"));
+/// assert!(!matcher.is_short_url("https://example.com/a1"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DomainMatcher {
+    domains: HashSet<String>,
+}
+
+impl DomainMatcher {
+    /// Creates new `DomainMatcher` of the known domains.
+    pub fn new() -> DomainMatcher {
+        DomainMatcher {
+            domains: KNOWN_DOMAINS.iter().map(|d| (*d).to_owned()).collect(),
+        }
+    }
+
+    /// Adds the domains to match.
+    pub fn with_domains<I, S>(mut self, domains: I) -> DomainMatcher
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.domains.extend(
+            domains
+                .into_iter()
+                .map(|d| d.as_ref().trim_end_matches('.').to_ascii_lowercase()),
+        );
+        self
+    }
+
+    /// Returns whether the host is one of the domains or one of their
+    /// subdomains.
+    pub fn matches_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let mut suffixes = domain_suffixes(&host);
+        suffixes.any(|domain| self.domains.contains(domain))
+    }
+
+    /// Returns whether the URL is on one of the domains or one of their
+    /// subdomains.
+    pub fn is_short_url(&self, url: &str) -> bool {
+        match host_of(url) {
+            Some(host) => self.matches_host(&host),
+            None => false,
+        }
+    }
+}
+
+impl Default for DomainMatcher {
+    fn default() -> DomainMatcher {
+        DomainMatcher::new()
+    }
+}
+
+/// Returns the lowercase host of the URL, without a trailing dot.
+#[cfg(feature = "url")]
+fn host_of(url: &str) -> Option<String> {
+    let url = url::Url::parse(url.trim()).ok()?;
+    Some(url.host_str()?.trim_end_matches('.').to_ascii_lowercase())
+}
+
+/// Returns the lowercase host of the URL, without a trailing dot. Unlike the
+/// `url` crate, the host is neither validated nor converted to punycode.
+#[cfg(not(feature = "url"))]
+fn host_of(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = &url[url.find("://")? + 3..];
+    let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+    let host = match authority.rfind('@') {
+        Some(i) => &authority[i + 1..],
+        None => authority,
+    };
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => &host[..ipv6.find(']')? + 2],
+        None => host.split(':').next().unwrap_or(host),
+    };
+    let host = host.trim_end_matches('.');
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_ascii_lowercase())
     }
-    .ok_or(ProviderError::Deserialize)
+}
+
+/// Returns the host along with all the domains it is a subdomain of, for
+/// example `a.b.c`, `b.c` and `c` for `a.b.c`.
+fn domain_suffixes(host: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(host).chain(host.match_indices('.').map(move |(i, _)| &host[i + 1..]))
 }
 
 /// Performs a request to the short link provider.
@@ -517,37 +3452,992 @@ pub fn parse(res: &str, provider: &Provider) -> Result<String, ProviderError> {
 ///
 /// fn main() {
 ///     let long_url = "https://google.com";
-///     let key = "MY_API_KEY";
-///     let req = providers::request(long_url, &Provider::GooGl { api_key: key.to_owned() });
-///     println!("A request object for shortening URL via GooGl: {:?}", req);
+///     let token = "MY_TOKEN";
+///     let req = providers::request(long_url, &Provider::BitLy { token: token.to_owned() });
+///     println!("A request object for shortening URL via BitLy: {:?}", req);
 /// }
 /// ```
+///
+/// The request is built even for the discontinued providers, use
+/// `request_with_options` to get an error instead. `Provider::Local` and
+/// `Provider::Fake` send no request: their request has an empty URL, which no
+/// client is able to send, use `shorten_locally` instead.
 pub fn request(url: &str, provider: &Provider) -> req::Request {
+    build_request(url, provider, &ShortenOptions::default())
+}
+
+/// Performs a request to the short link provider passing the additional
+/// link options.
+///
+/// Returns `ProviderError::Discontinued` if the provider service has been
+/// shut down. The options which the provider is not able to honour either
+/// cause `ProviderError::UnsupportedOption` or are dropped, depending on the
+/// `ShortenOptions::mode`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::{options::ShortenOptions, providers::{self, Provider}};
+///
+/// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+/// let options = ShortenOptions::new().title("Google").tag("search");
+/// let req = providers::request_with_options("https://google.com", &provider, &options);
+/// println!("A request object for shortening URL via BitLy: {:?}", req);
+/// ```
+#[allow(deprecated)]
+pub fn request_with_options(
+    url: &str,
+    provider: &Provider,
+    options: &ShortenOptions,
+) -> Result<req::Request, ProviderError> {
+    match *provider {
+        Provider::GooGl { .. } => return Err(ProviderError::Discontinued),
+        Provider::Fake { .. } | Provider::Local { .. } => return Err(ProviderError::Unsupported),
+        _ => {}
+    }
+    check_scheme(url, provider, &options.schemes)?;
+    let options = negotiate_options(provider, options)?;
+
+    Ok(build_request(url, provider, &options))
+}
+
+/// Creates the short URL of the providers which send no request,
+/// `Provider::Local` and `Provider::Fake`, passing the additional link
+/// options. The options are checked as they are by `request_with_options`.
+///
+/// Returns `ProviderError::Unsupported` for the other providers.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use urlshortener::{local::MemoryStore, options::ShortenOptions, providers::{self, Provider}};
+///
+/// let provider = Provider::Local {
+///     base_url: "http://localhost:8080/s".to_owned(),
+///     store: Arc::new(MemoryStore::new()),
+/// };
+/// let options = ShortenOptions::default();
+/// let short_url = providers::shorten_locally("https://rust-lang.org", &provider, &options).unwrap();
+/// assert_eq!(short_url, "http://localhost:8080/s/0");
+/// assert_eq!(
+///     providers::expand_locally(&short_url, &provider).unwrap(),
+///     "https://rust-lang.org"
+/// );
+/// ```
+pub fn shorten_locally(
+    url: &str,
+    provider: &Provider,
+    options: &ShortenOptions,
+) -> Result<String, ProviderError> {
+    match *provider {
+        Provider::Local {
+            ref base_url,
+            ref store,
+        } => {
+            check_scheme(url, provider, &options.schemes)?;
+            let options = negotiate_options(provider, options)?;
+            local::shorten(base_url, &**store, url, options.alias.as_deref())
+        }
+        Provider::Fake { ref pattern } => {
+            check_scheme(url, provider, &options.schemes)?;
+            let options = negotiate_options(provider, options)?;
+            Ok(local::fake(pattern, url, options.alias.as_deref()))
+        }
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Returns the URL stored for the short URL of `Provider::Local`.
+///
+/// Returns `ProviderError::InvalidUrl` if the short URL is unknown, and
+/// `ProviderError::Unsupported` for the other providers.
+pub fn expand_locally(short_url: &str, provider: &Provider) -> Result<String, ProviderError> {
+    match *provider {
+        Provider::Local {
+            ref base_url,
+            ref store,
+        } => local::expand(base_url, &**store, short_url),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Creates a request like `request_with_options` does, with the provider
+/// credential obtained from the credential provider instead of the one stored
+/// in the `Provider` variant.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::{
+///     credentials::Secret,
+///     options::ShortenOptions,
+///     providers::{self, Provider},
+/// };
+///
+/// let provider = Provider::BitLy { token: String::new() };
+/// let credentials = Secret::new("MY_TOKEN");
+/// let options = ShortenOptions::default();
+/// let req = providers::request_with_credentials("https://google.com", &provider, &options, &credentials);
+/// println!("A request object for shortening URL via BitLy: {:?}", req);
+/// ```
+pub fn request_with_credentials(
+    url: &str,
+    provider: &Provider,
+    options: &ShortenOptions,
+    credentials: &dyn CredentialProvider,
+) -> Result<req::Request, ProviderError> {
+    let provider = provider.with_credential(&credentials.token(provider)?);
+    request_with_options(url, &provider, options)
+}
+
+/// Checks that the URL scheme is accepted according to the scheme policy.
+fn check_scheme(
+    url: &str,
+    provider: &Provider,
+    policy: &SchemePolicy,
+) -> Result<(), ProviderError> {
+    let scheme = match url.split_once(':') {
+        Some((scheme, _))
+            if scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) =>
+        {
+            scheme.to_ascii_lowercase()
+        }
+        _ if *policy == SchemePolicy::Any => return Ok(()),
+        _ => return Err(ProviderError::InvalidUrl),
+    };
+
+    let accepted = match *policy {
+        SchemePolicy::Provider => provider.schemes().contains(&scheme.as_str()),
+        SchemePolicy::Allow(ref schemes) => schemes.contains(&scheme),
+        SchemePolicy::Any => true,
+    };
+    if accepted {
+        Ok(())
+    } else {
+        Err(ProviderError::InvalidUrl)
+    }
+}
+
+/// Checks that the provider is able to honour the options according to the
+/// options mode, returning the options to use.
+fn negotiate_options(
+    provider: &Provider,
+    options: &ShortenOptions,
+) -> Result<ShortenOptions, ProviderError> {
+    let mut options = options.clone();
+
+    for kind in options.kinds() {
+        if provider.supports(kind) {
+            continue;
+        }
+
+        match options.mode {
+            OptionsMode::Strict => return Err(ProviderError::UnsupportedOption(kind)),
+            OptionsMode::Lenient => options.clear(kind),
+        }
+    }
+
+    // The desktop browsers are sent to the URL shortened by OneLink, there is
+    // no other web URL to set.
+    if let Provider::OneLink { .. } = *provider {
+        if let Some(ref mut deep_link) = options.deep_link {
+            if deep_link.desktop_url.is_some() {
+                match options.mode {
+                    OptionsMode::Strict => {
+                        return Err(ProviderError::UnsupportedOption(OptionKind::DeepLink))
+                    }
+                    OptionsMode::Lenient => deep_link.desktop_url = None,
+                }
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Returns a request with an empty URL, which no client is able to send, for
+/// the providers creating the short URLs without a request.
+fn unsendable_req() -> req::Request {
+    req::Request {
+        url: String::new(),
+        body: None,
+        content_type: None,
+        user_agent: None,
+        headers: None,
+        method: req::Method::Get,
+    }
+}
+
+#[allow(deprecated)]
+fn build_request(url: &str, provider: &Provider, options: &ShortenOptions) -> req::Request {
     match *provider {
         Provider::Abv8 => abv8_req(url),
+        Provider::AdfLy {
+            ref api_key,
+            ref user_id,
+            advert_type,
+        } => adfly_req(url, api_key, user_id, advert_type),
         Provider::BamBz => bambz_req(url),
-        Provider::BitLy { ref token } => bitly_req(url, token),
+        Provider::BitLy { ref token } => bitly_req(url, token, options),
         Provider::BitUrl => biturl_req(url),
+        Provider::Blink {
+            ref api_token,
+            domain_id,
+        } => blink_req(url, api_token, domain_id, options),
         Provider::Bmeo => bmeo_req(url),
+        Provider::Branch { ref key } => branch_req(url, key, options),
+        Provider::ChhotoUrl {
+            ref api_key,
+            ref host,
+        } => chhotourl_req(url, api_key, host, options),
+        Provider::CleanUri => cleanuri_req(url),
+        Provider::ClckRu => clckru_req(url),
+        Provider::Cuttly { ref api_key } => cuttly_req(url, api_key, options),
         Provider::FifoCc => fifocc_req(url),
+        Provider::GgGg => gggg_req(url, options),
         Provider::GooGl { ref api_key } => googl_req(url, api_key),
         Provider::HmmRs => hmmrs_req(url),
         Provider::HecSu => hecsu_req(url),
-        Provider::IsGd => isgd_req(url),
+        Provider::IsGd => isgd_req(url, options),
         Provider::Kutt {
             ref api_key,
             ref host,
-        } => kutt_req(url, api_key, host.as_ref().map(|h| &**h)),
+        } => kutt_req(url, api_key, host.as_ref().map(|h| &**h), options),
+        Provider::LihiCc {
+            ref token,
+            ref split_urls,
+        } => lihicc_req(url, token, split_urls, options),
+        Provider::Fake { .. } => unsendable_req(),
+        Provider::LnnkIn => lnnkin_req(url, options),
+        Provider::Local { .. } => unsendable_req(),
+        Provider::MgnetMe => mgnetme_req(url),
+        Provider::N9Cl { ref api_key } => n9cl_req(url, api_key.as_deref()),
         Provider::NowLinks => nowlinks_req(url),
+        Provider::OneLink {
+            ref template_id,
+            ref api_token,
+        } => onelink_req(url, template_id, api_token, options),
+        Provider::OuoIo { ref api_key } => ouoio_req(url, api_key),
         Provider::PhxCoIn => phxcoin_req(url),
         Provider::PsbeCo => psbeco_req(url),
+        Provider::PseIs { ref token } => pseis_req(url, token, options),
+        Provider::SnipLy {
+            ref token,
+            ref cta,
+            ref campaign,
+        } => sniply_req(url, token, cta.as_deref(), campaign.as_deref()),
+        Provider::SpooMe => spoome_req(url, options),
         Provider::SCoop => scoop_req(url),
+        Provider::ShortIo {
+            ref api_key,
+            ref domain,
+        } => shortio_req(url, api_key, domain, options),
+        Provider::ShorteSt { ref api_token } => shortest_req(url, api_token),
+        Provider::ShrtcoDe => shrtcode_req(url),
+        Provider::SId {
+            ref client_id,
+            ref api_key,
+        } => sid_req(url, client_id, api_key, options),
         Provider::SirBz => sirbz_req(url),
+        Provider::Rebrandly {
+            ref api_key,
+            ref domain,
+        } => rebrandly_req(url, api_key, domain.as_deref(), options),
         Provider::Rlu => rlu_req(url),
+        Provider::T2m { ref api_key } => t2m_req(url, api_key, options),
         Provider::TinyUrl => tinyurl_req(url),
+        Provider::TinyUrlApi { ref token } => tinyurlapi_req(url, token, options),
         Provider::TinyPh => tinyph_req(url),
-        Provider::TnyIm => tnyim_req(url),
+        Provider::TnyIm => yourls_shorten_req("http://tny.im", &YourlsAuth::Public, url, options),
         Provider::UrlShortenerIo => urlshortenerio_req(url),
-        Provider::VGd => vgd_req(url),
+        Provider::VGd => vgd_req(url, options),
+        Provider::VHt => vht_req(url),
+        Provider::VurlCom => vurlcom_req(url),
+        Provider::Yourls {
+            ref base_url,
+            ref auth,
+        } => yourls_shorten_req(base_url, auth, url, options),
+    }
+}
+
+/// Creates a request which resolves a short URL back into the original one
+/// via the provider API. This works even when following the redirect itself
+/// is not possible, for example, when it is blocked by a proxy.
+///
+/// Returns `ProviderError::Unsupported` if the provider has no such API.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::providers::{self, Provider};
+///
+/// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+/// let req = providers::expand_request("https://bit.ly/2Ewn0ZT", &provider);
+/// println!("A request object for expanding URL via BitLy: {:?}", req);
+/// ```
+pub fn expand_request(short_url: &str, provider: &Provider) -> Result<req::Request, ProviderError> {
+    match *provider {
+        Provider::BitLy { ref token } => Ok(bitly_expand_req(short_url, token)),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Parses the response from a successful expand request to a provider into
+/// the original URL.
+pub fn parse_expand(res: &str, provider: &Provider) -> Result<String, ProviderError> {
+    match *provider {
+        Provider::BitLy { .. } => bitly_expand_parse(res).ok_or(ProviderError::Deserialize),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Creates a single request which shortens all the URLs at once via the
+/// provider bulk API. The URLs must not outnumber `Provider::max_bulk_size`.
+///
+/// The `ShortenOptions::alias` is not applied, as the links can not share it.
+///
+/// Returns `ProviderError::Unsupported` if the provider has no such API.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::{options::ShortenOptions, providers::{self, Provider}};
+///
+/// let provider = Provider::ShortIo {
+///     api_key: "MY_API_KEY".to_owned(),
+///     domain: "example.com".to_owned(),
+/// };
+/// let urls = ["https://google.com", "https://rust-lang.org"];
+/// let req = providers::bulk_request(&urls, &provider, &ShortenOptions::default());
+/// println!("A request object for shortening URLs via Short.io: {:?}", req);
+/// ```
+pub fn bulk_request(
+    urls: &[&str],
+    provider: &Provider,
+    options: &ShortenOptions,
+) -> Result<req::Request, ProviderError> {
+    for url in urls {
+        check_scheme(url, provider, &options.schemes)?;
+    }
+    let options = negotiate_options(provider, options)?;
+
+    match *provider {
+        Provider::ShortIo {
+            ref api_key,
+            ref domain,
+        } => Ok(shortio_bulk_req(urls, api_key, domain, &options)),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Parses the response from a successful bulk request to a provider into the
+/// short URLs, in the order of the requested URLs. The URLs the provider
+/// refused to shorten are reported as errors.
+pub fn parse_bulk(
+    res: &str,
+    provider: &Provider,
+) -> Result<Vec<Result<String, ProviderError>>, ProviderError> {
+    match *provider {
+        Provider::ShortIo { .. } => shortio_bulk_parse(res).ok_or(ProviderError::Deserialize),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Creates a request which retrieves the click statistics of a short URL over
+/// the given period via the provider API.
+///
+/// The is.gd and v.gd statistics are only available for the links created
+/// with `ShortenOptions::log_stats` enabled. The is.gd, v.gd and tny.im
+/// statistics are always reported for the whole link lifetime, ignoring the
+/// period.
+///
+/// Returns `ProviderError::Unsupported` if the provider has no such API.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::{providers::{self, Provider}, stats::StatsPeriod};
+///
+/// let provider = Provider::BitLy { token: "MY_TOKEN".to_owned() };
+/// let period = StatsPeriod::default();
+/// let req = providers::stats_request("https://bit.ly/2Ewn0ZT", &provider, &period);
+/// println!("A request object for link statistics via BitLy: {:?}", req);
+/// ```
+pub fn stats_request(
+    short_url: &str,
+    provider: &Provider,
+    period: &StatsPeriod,
+) -> Result<req::Request, ProviderError> {
+    match *provider {
+        Provider::BitLy { ref token } => Ok(bitly_stats_req(short_url, token, period)),
+        Provider::IsGd => Ok(gd_stats_req("https://is.gd", short_url)),
+        Provider::VGd => Ok(gd_stats_req("https://v.gd", short_url)),
+        Provider::TnyIm => Ok(yourls_stats_req(
+            "http://tny.im",
+            &YourlsAuth::Public,
+            short_url,
+        )),
+        Provider::Yourls {
+            ref base_url,
+            ref auth,
+        } => Ok(yourls_stats_req(base_url, auth, short_url)),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Parses the response from a successful statistics request to a provider.
+pub fn parse_stats(res: &str, provider: &Provider) -> Result<LinkStats, ProviderError> {
+    match *provider {
+        Provider::BitLy { .. } => bitly_stats_parse(res).ok_or(ProviderError::Deserialize),
+        Provider::IsGd | Provider::VGd => gd_stats_parse(res).ok_or(ProviderError::Deserialize),
+        Provider::TnyIm | Provider::Yourls { .. } => {
+            yourls_stats_parse(res).ok_or(ProviderError::Deserialize)
+        }
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Creates a request which lists the links stored on the provider side,
+/// skipping the first `skip` links and returning at most `limit` of them.
+///
+/// Returns `ProviderError::Unsupported` if the provider has no such API.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use urlshortener::providers::{self, Provider};
+///
+/// let provider = Provider::Kutt { api_key: "MY_API_KEY".to_owned(), host: None };
+/// let req = providers::list_links_request(&provider, 0, 10);
+/// println!("A request object for listing links via Kutt: {:?}", req);
+/// ```
+pub fn list_links_request(
+    provider: &Provider,
+    skip: u64,
+    limit: u64,
+) -> Result<req::Request, ProviderError> {
+    match *provider {
+        Provider::Kutt {
+            ref api_key,
+            ref host,
+        } => Ok(kutt_list_links_req(
+            api_key,
+            host.as_ref().map(|h| &**h),
+            skip,
+            limit,
+        )),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Parses the response from a successful link listing request to a provider.
+pub fn parse_list_links(res: &str, provider: &Provider) -> Result<LinkPage, ProviderError> {
+    match *provider {
+        Provider::Kutt { .. } => kutt_list_links_parse(res).ok_or(ProviderError::Deserialize),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Creates a request which deletes the link with the given provider
+/// identifier (see `Link::id`).
+///
+/// Returns `ProviderError::Unsupported` if the provider has no such API.
+pub fn delete_request(id: &str, provider: &Provider) -> Result<req::Request, ProviderError> {
+    match *provider {
+        Provider::Kutt {
+            ref api_key,
+            ref host,
+        } => Ok(kutt_delete_req(id, api_key, host.as_ref().map(|h| &**h))),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+/// Parses the response from a successful link deletion request to a provider.
+pub fn parse_delete(res: &str, provider: &Provider) -> Result<(), ProviderError> {
+    match *provider {
+        Provider::Kutt { .. } => kutt_delete_parse(res).ok_or(ProviderError::Deserialize),
+        _ => Err(ProviderError::Unsupported),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::DeepLink;
+
+    #[test]
+    fn provider_parsers_match_parse() {
+        type Parser = fn(&str) -> Result<String, ProviderError>;
+
+        // No wildcard, so that a new provider needs its parser module.
+        #[allow(deprecated)]
+        fn parser_of(provider: &Provider) -> Option<Parser> {
+            match *provider {
+                Provider::Abv8 => Some(abv8::parse_response),
+                Provider::AdfLy { .. } => Some(adfly::parse_response),
+                Provider::BamBz => Some(bambz::parse_response),
+                Provider::BitLy { .. } => Some(bitly::parse_response),
+                Provider::BitUrl => Some(biturl::parse_response),
+                Provider::Blink { .. } => Some(blink::parse_response),
+                Provider::Bmeo => Some(bmeo::parse_response),
+                Provider::Branch { .. } => Some(branch::parse_response),
+                Provider::ChhotoUrl { .. } => Some(chhotourl::parse_response),
+                Provider::CleanUri => Some(cleanuri::parse_response),
+                Provider::ClckRu => Some(clckru::parse_response),
+                Provider::Cuttly { .. } => Some(cuttly::parse_response),
+                Provider::FifoCc => Some(fifocc::parse_response),
+                Provider::GgGg => Some(gggg::parse_response),
+                Provider::HmmRs => Some(hmmrs::parse_response),
+                Provider::HecSu => Some(hecsu::parse_response),
+                Provider::IsGd => Some(isgd::parse_response),
+                Provider::Kutt { .. } => Some(kutt::parse_response),
+                Provider::LihiCc { .. } => Some(lihicc::parse_response),
+                Provider::LnnkIn => Some(lnnkin::parse_response),
+                Provider::MgnetMe => Some(mgnetme::parse_response),
+                Provider::N9Cl { .. } => Some(n9cl::parse_response),
+                Provider::NowLinks => Some(nowlinks::parse_response),
+                Provider::OneLink { .. } => Some(onelink::parse_response),
+                Provider::OuoIo { .. } => Some(ouoio::parse_response),
+                Provider::PhxCoIn => Some(phxcoin::parse_response),
+                Provider::PsbeCo => Some(psbeco::parse_response),
+                Provider::PseIs { .. } => Some(pseis::parse_response),
+                Provider::SnipLy { .. } => Some(sniply::parse_response),
+                Provider::SpooMe => Some(spoome::parse_response),
+                Provider::SCoop => Some(scoop::parse_response),
+                Provider::ShortIo { .. } => Some(shortio::parse_response),
+                Provider::ShorteSt { .. } => Some(shortest::parse_response),
+                Provider::ShrtcoDe => Some(shrtcode::parse_response),
+                Provider::SId { .. } => Some(sid::parse_response),
+                Provider::SirBz => Some(sirbz::parse_response),
+                Provider::Rebrandly { .. } => Some(rebrandly::parse_response),
+                Provider::Rlu => Some(rlu::parse_response),
+                Provider::T2m { .. } => Some(t2m::parse_response),
+                Provider::TinyUrl => Some(tinyurl::parse_response),
+                Provider::TinyUrlApi { .. } => Some(tinyurlapi::parse_response),
+                Provider::TinyPh => Some(tinyph::parse_response),
+                Provider::TnyIm => Some(tnyim::parse_response),
+                Provider::UrlShortenerIo => Some(urlshortenerio::parse_response),
+                Provider::VGd => Some(vgd::parse_response),
+                Provider::VHt => Some(vht::parse_response),
+                Provider::VurlCom => Some(vurlcom::parse_response),
+                Provider::Yourls { .. } => Some(yourls::parse_response),
+                Provider::Fake { .. } | Provider::GooGl { .. } | Provider::Local { .. } => None,
+            }
+        }
+
+        for fixture in crate::fixtures::all() {
+            if let Some(parse_response) = parser_of(&fixture.provider) {
+                assert_eq!(
+                    parse_response(fixture.body),
+                    parse(fixture.body, &fixture.provider),
+                    "{}",
+                    fixture.description
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gd_json_response() {
+        let ok = r#"{ "shorturl": "https://is.gd/MOgh5q" }"#;
+        assert_eq!(
+            parse(ok, &Provider::IsGd),
+            Ok("https://is.gd/MOgh5q".to_owned())
+        );
+
+        let errors = [
+            (1, IsGdError::InvalidUrl, ProviderError::InvalidUrl),
+            (2, IsGdError::InvalidAlias, ProviderError::AliasTaken),
+            (3, IsGdError::RateLimited, ProviderError::RateLimited),
+            (4, IsGdError::Other, ProviderError::Unavailable),
+        ];
+        for (code, error, kind) in errors {
+            let res = format!(r#"{{ "errorcode": {}, "errormessage": "Error" }}"#, code);
+            let err = parse(&res, &Provider::VGd).unwrap_err();
+            assert_eq!(err, ProviderError::Provider(ProviderCode::IsGd(error)));
+            assert_eq!(err.kind(), kind);
+        }
+
+        assert_eq!(
+            parse("<html></html>", &Provider::IsGd),
+            Err(ProviderError::Deserialize)
+        );
+    }
+
+    #[test]
+    fn bitly_expands_normalised_bitlinks() {
+        let provider = Provider::BitLy {
+            token: "MY_TOKEN".to_owned(),
+        };
+        for short_url in [
+            "https://bit.ly/2Ewn0ZT",
+            "http://Bit.ly/2Ewn0ZT/",
+            "https://bit.ly/2Ewn0ZT?utm_source=x#top",
+            "bit.ly/2Ewn0ZT",
+        ] {
+            let req = expand_request(short_url, &provider).unwrap();
+            assert_eq!(
+                req.body.as_deref(),
+                Some(r#"{"bitlink_id":"bit.ly/2Ewn0ZT"}"#),
+                "{}",
+                short_url
+            );
+        }
+    }
+
+    #[test]
+    fn bitly_stats_encode_the_bitlink() {
+        let provider = Provider::BitLy {
+            token: "MY_TOKEN".to_owned(),
+        };
+        let req = stats_request(
+            "https://bit.ly/2Ewn0ZT/?utm_source=x#top",
+            &provider,
+            &StatsPeriod::default(),
+        )
+        .unwrap();
+        assert!(
+            req.url.starts_with(
+                "https://api-ssl.bitly.com/v4/bitlinks/bit.ly%2F2Ewn0ZT/clicks/summary?"
+            ),
+            "{}",
+            req.url
+        );
+    }
+
+    #[test]
+    fn gd_requests_json() {
+        for provider in [Provider::IsGd, Provider::VGd] {
+            let url = request("https://rust-lang.org", &provider).url;
+            assert!(url.contains("format=json"), "{}", url);
+        }
+    }
+
+    #[test]
+    fn gd_stats_page() {
+        let page = "<html><body><h2>Statistics</h2><p>Total hits: <b>1,024</b></p></body></html>";
+        assert_eq!(
+            parse_stats(page, &Provider::IsGd),
+            Ok(LinkStats { clicks: 1024 })
+        );
+        assert_eq!(
+            parse_stats("<html></html>", &Provider::VGd),
+            Err(ProviderError::Deserialize)
+        );
+    }
+
+    #[test]
+    fn alias_support() {
+        let options = ShortenOptions::new().alias("my-slug");
+        let url = "https://rust-lang.org";
+
+        let req = request_with_options(url, &Provider::IsGd, &options).unwrap();
+        assert!(req.url.contains("shorturl=my-slug"));
+        assert_eq!(
+            request_with_options(url, &Provider::BamBz, &options).err(),
+            Some(ProviderError::UnsupportedOption(OptionKind::Alias))
+        );
+
+        let options = options.mode(OptionsMode::Lenient);
+        assert!(request_with_options(url, &Provider::BamBz, &options).is_ok());
+    }
+
+    #[test]
+    fn slow_tier_saturates() {
+        let timeout = Duration::from_secs(3);
+        assert_eq!(TimeoutTier::Slow.scale(timeout), Duration::from_secs(6));
+        assert_eq!(TimeoutTier::Slow.scale(Duration::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn preview_is_not_a_request_option() {
+        let options = ShortenOptions::new().preview(true);
+        assert_eq!(
+            request_with_options("https://rust-lang.org", &Provider::IsGd, &options).err(),
+            Some(ProviderError::UnsupportedOption(OptionKind::Preview))
+        );
+    }
+
+    #[test]
+    fn local_providers_send_no_request() {
+        let providers = [
+            Provider::Local {
+                base_url: "http://localhost:8080".to_owned(),
+                store: Arc::new(local::MemoryStore::new()),
+            },
+            Provider::Fake {
+                pattern: "https://fake.link".to_owned(),
+            },
+        ];
+        for provider in providers.iter() {
+            assert_eq!(request("https://rust-lang.org", provider).url, "");
+            assert_eq!(
+                parse("https://fake.link/a1", provider),
+                Err(ProviderError::Unsupported)
+            );
+        }
+    }
+
+    #[test]
+    fn onelink_keeps_shortened_url() {
+        let provider = Provider::OneLink {
+            template_id: "abc".to_owned(),
+            api_token: "MY_TOKEN".to_owned(),
+        };
+        let url = "https://rust-lang.org";
+        let deep_link = DeepLink::new()
+            .ios_url("https://apps.apple.com/app/id1")
+            .desktop_url("https://example.com");
+        let options = ShortenOptions::new().deep_link(deep_link);
+        assert_eq!(
+            request_with_options(url, &provider, &options).err(),
+            Some(ProviderError::UnsupportedOption(OptionKind::DeepLink))
+        );
+
+        let options = options.mode(OptionsMode::Lenient);
+        let body = request_with_options(url, &provider, &options)
+            .unwrap()
+            .body
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["data"]["af_web_dp"], url);
+        assert_eq!(json["data"]["af_ios_url"], "https://apps.apple.com/app/id1");
+    }
+
+    #[test]
+    fn yourls_json_response() {
+        let provider = Provider::Yourls {
+            base_url: "https://sho.rt/".to_owned(),
+            auth: YourlsAuth::Signature("1002a612b4".to_owned()),
+        };
+        let req = request("https://rust-lang.org", &provider);
+        assert!(req
+            .url
+            .starts_with("https://sho.rt/yourls-api.php?action=shorturl"));
+        assert!(req.url.ends_with("&format=json&signature=1002a612b4"));
+
+        let exists = r#"{"status":"fail","code":"error:url","shorturl":"https://sho.rt/r"}"#;
+        assert_eq!(parse(exists, &provider), Ok("https://sho.rt/r".to_owned()));
+        let taken = r#"{"status":"fail","code":"error:keyword","message":"taken"}"#;
+        assert_eq!(parse(taken, &provider), Err(ProviderError::AliasTaken));
+    }
+
+    #[test]
+    fn cuttly_status_codes() {
+        let provider = Provider::Cuttly {
+            api_key: "MY_API_KEY".to_owned(),
+        };
+        let ok = r#"{"url":{"status":7,"shortLink":"https:\/\/cutt.ly\/a"}}"#;
+        assert_eq!(parse(ok, &provider), Ok("https://cutt.ly/a".to_owned()));
+
+        let errors = [
+            (2, CuttlyError::NotALink, ProviderError::InvalidUrl),
+            (3, CuttlyError::AliasTaken, ProviderError::AliasTaken),
+            (4, CuttlyError::InvalidApiKey, ProviderError::Unauthorized),
+            (8, CuttlyError::LimitReached, ProviderError::RateLimited),
+        ];
+        for (status, error, kind) in errors {
+            let res = format!(r#"{{"url":{{"status":{}}}}}"#, status);
+            let err = parse(&res, &provider).unwrap_err();
+            assert_eq!(err, ProviderError::Provider(ProviderCode::Cuttly(error)));
+            assert_eq!(err.kind(), kind);
+        }
+    }
+
+    #[test]
+    fn shortio_bulk_response() {
+        let provider = Provider::ShortIo {
+            api_key: "MY_API_KEY".to_owned(),
+            domain: "sho.rt".to_owned(),
+        };
+        let res = r#"[{"originalURL":"https://a.com","shortURL":"https://sho.rt/a"},
+            {"originalURL":"a","error":"Invalid url"}]"#;
+        assert_eq!(
+            parse_bulk(res, &provider),
+            Ok(vec![
+                Ok("https://sho.rt/a".to_owned()),
+                Err(ProviderError::InvalidUrl)
+            ])
+        );
+        assert_eq!(
+            parse_bulk(res, &Provider::IsGd),
+            Err(ProviderError::Unsupported)
+        );
+    }
+
+    #[test]
+    fn credentials_replace_token() {
+        let provider = Provider::Kutt {
+            api_key: String::new(),
+            host: Some("https://sho.rt".to_owned()),
+        };
+        let credentials = Secret::new("MY_API_KEY");
+        let options = ShortenOptions::default();
+        let req =
+            request_with_credentials("https://a.com", &provider, &options, &credentials).unwrap();
+        assert!(req.url.starts_with("https://sho.rt/"));
+        assert_eq!(req.headers.unwrap()["X-API-Key"], "MY_API_KEY");
+    }
+
+    #[test]
+    fn scheme_policy() {
+        let options = ShortenOptions::default();
+        for url in [
+            "javascript:alert(1)",
+            "data:text/html,hi",
+            "file:///etc/passwd",
+            "rust-lang.org",
+        ] {
+            assert_eq!(
+                request_with_options(url, &Provider::IsGd, &options).err(),
+                Some(ProviderError::InvalidUrl)
+            );
+        }
+        assert!(request_with_options("HTTPS://rust-lang.org", &Provider::IsGd, &options).is_ok());
+
+        let magnet = "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+        assert!(request_with_options(magnet, &Provider::MgnetMe, &options).is_ok());
+        assert!(request_with_options(magnet, &Provider::IsGd, &options).is_err());
+
+        let options = options.schemes(SchemePolicy::Allow(vec!["ftp".to_owned()]));
+        assert!(request_with_options("ftp://ftp.gnu.org", &Provider::IsGd, &options).is_ok());
+    }
+
+    #[test]
+    fn provider_equality() {
+        use std::collections::HashSet;
+
+        let bitly = |token: &str| Provider::BitLy {
+            token: token.to_owned(),
+        };
+        assert_eq!(bitly("a"), bitly("a"));
+        assert_ne!(bitly("a"), bitly("b"));
+        assert_ne!(Provider::IsGd, Provider::VGd);
+
+        let set: HashSet<Provider> = vec![bitly("a"), bitly("a"), bitly("b"), Provider::IsGd]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 3);
+
+        let yourls = |auth| Provider::Yourls {
+            base_url: "https://sho.rt".to_owned(),
+            auth,
+        };
+        assert_ne!(
+            yourls(YourlsAuth::Password {
+                username: "signature".to_owned(),
+                password: "x".to_owned(),
+            }),
+            yourls(YourlsAuth::Signature("x".to_owned()))
+        );
+    }
+
+    #[test]
+    fn providers_are_known_shorteners() {
+        // The domains are looked up with a binary search.
+        assert!(KNOWN_DOMAINS.windows(2).all(|w| w[0] < w[1]));
+
+        for provider in crate::fixtures::providers() {
+            let self_hosted = matches!(
+                provider,
+                Provider::ChhotoUrl { .. }
+                    | Provider::Fake { .. }
+                    | Provider::Local { .. }
+                    | Provider::Yourls { .. }
+                    | Provider::ShortIo { .. }
+            );
+            if !self_hosted {
+                let url = format!("https://{}/a1", provider.to_name());
+                assert!(is_short_url(&url), "{} is not known", url);
+            }
+        }
+    }
+
+    #[test]
+    fn parsers_never_panic() {
+        const FRAGMENTS: &[&str] = &[
+            "{",
+            "}",
+            "[",
+            "]",
+            "\"",
+            ":",
+            ",",
+            "\\",
+            "<",
+            ">",
+            "</",
+            "null",
+            "7",
+            "-1",
+            "1e999",
+            "\"shorturl\"",
+            "\"link\"",
+            "\"url\"",
+            "\"status\"",
+            "\"errorcode\"",
+            "\"result\"",
+            "\"data\"",
+            "\"error\"",
+            "\"message\"",
+            "ShortUrl",
+            "short",
+            "https://",
+            "é",
+            "\u{0}",
+            "\n",
+            " ",
+        ];
+
+        let mut inputs = vec![
+            String::new(),
+            "{}".to_owned(),
+            "[]".to_owned(),
+            "null".to_owned(),
+        ];
+        // A xorshift generator keeps the inputs the same between the runs.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..500 {
+            let len = next() % 24;
+            let input = (0..len)
+                .map(|_| FRAGMENTS[(next() % FRAGMENTS.len() as u64) as usize])
+                .collect::<String>();
+            inputs.push(input);
+
+            let bytes = (0..next() % 64).map(|_| next() as u8).collect::<Vec<u8>>();
+            inputs.push(String::from_utf8_lossy(&bytes).into_owned());
+        }
+
+        for provider in crate::fixtures::providers() {
+            for input in &inputs {
+                let _ = parse(input, &provider);
+                let _ = parse_expand(input, &provider);
+                let _ = parse_bulk(input, &provider);
+                let _ = parse_stats(input, &provider);
+                let _ = parse_list_links(input, &provider);
+                let _ = parse_delete(input, &provider);
+            }
+        }
+        for input in &inputs {
+            let _ = parse_shrtcode_links(input);
+            let _ = parse_t2m_link(input);
+        }
+
+        let provider = Provider::Kutt {
+            api_key: "KEY\r\n".to_owned(),
+            host: None,
+        };
+        let req = request_with_options("https://a.com", &provider, &ShortenOptions::default());
+        assert_eq!(req.unwrap().headers.unwrap()["X-API-Key"], "");
     }
 }
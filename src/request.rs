@@ -1,11 +1,15 @@
+use http::header::HeaderMap;
 #[cfg(feature = "client")]
 use reqwest::{
-    blocking::{Client, Response},
-    header::{self, HeaderMap},
+    blocking::{Client, RequestBuilder, Response},
+    header,
 };
 
-const CONTENT_JSON: &str = "application/json";
-const CONTENT_FORM_URL_ENCODED: &str = "application/x-www-form-urlencoded";
+use std::fmt;
+
+pub(crate) const CONTENT_JSON: &str = "application/json";
+#[cfg(feature = "client")]
+pub(crate) const CONTENT_FORM_URL_ENCODED: &str = "application/x-www-form-urlencoded";
 
 /// An HTTP method abstraction
 #[derive(Debug, Copy, Clone)]
@@ -14,6 +18,22 @@ pub enum Method {
     Get,
     /// `POST` HTTP method should be used.
     Post,
+    /// `PUT` HTTP method should be used.
+    Put,
+    /// `DELETE` HTTP method should be used.
+    Delete,
+}
+
+impl Method {
+    /// Returns the uppercase name of the method.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        }
+    }
 }
 
 /// An HTTP content type abstraction
@@ -30,7 +50,12 @@ pub enum ContentType {
 pub struct UserAgent(pub String);
 
 /// An abstraction for basic http request.
-#[derive(Debug, Clone)]
+///
+/// The `Debug` output hides the values of the headers, the query parameters
+/// and the body fields which are named like credentials, such as
+/// `Authorization`, `api_key` or `token`. The credentials sent in the path of
+/// the URL are not recognized.
+#[derive(Clone)]
 pub struct Request {
     /// The URL the request must be sent to.
     pub url: String,
@@ -46,24 +71,117 @@ pub struct Request {
     pub method: Method,
 }
 
+impl fmt::Debug for Request {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let headers = self.headers.as_ref().map(|headers| {
+            headers
+                .iter()
+                .map(|(name, value)| {
+                    let value = if is_secret_name(name.as_str()) {
+                        REDACTED
+                    } else {
+                        value.to_str().unwrap_or("<binary>")
+                    };
+                    (name.as_str(), value)
+                })
+                .collect::<Vec<_>>()
+        });
+        let body = self.body.as_deref().map(|body| match self.content_type {
+            Some(ContentType::Json) => redact_json(body),
+            _ => redact_query(body),
+        });
+
+        f.debug_struct("Request")
+            .field("url", &redact_url(&self.url))
+            .field("body", &body)
+            .field("content_type", &self.content_type)
+            .field("user_agent", &self.user_agent)
+            .field("headers", &headers)
+            .field("method", &self.method)
+            .finish()
+    }
+}
+
+const REDACTED: &str = "***";
+
+/// Returns whether the header, parameter or field is named like a
+/// credential.
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "key" | "apikey" | "signature" | "authorization" | "auth" | "uid"
+    ) || ["key", "token", "secret", "password"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix) && !name.ends_with("keyword"))
+}
+
+fn redact_url(url: &str) -> String {
+    match url.split_once('?') {
+        Some((path, query)) => format!("{}?{}", path, redact_query(query)),
+        None => url.to_owned(),
+    }
+}
+
+fn redact_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_secret_name(name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn redact_json(body: &str) -> String {
+    fn redact(value: &mut serde_json::Value) {
+        match *value {
+            serde_json::Value::Object(ref mut fields) => {
+                for (name, value) in fields.iter_mut() {
+                    if is_secret_name(name) {
+                        *value = REDACTED.into();
+                    } else {
+                        redact(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(ref mut values) => values.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(mut json) => {
+            redact(&mut json);
+            json.to_string()
+        }
+        Err(_) => redact_query(body),
+    }
+}
+
+/// Builds the request with the client, the blocking or the async one, which
+/// have the same builder methods.
 #[cfg(feature = "client")]
-impl Request {
-    /// Sends the request and returns the response.
-    pub fn execute(&self, client: &Client) -> Result<Response, reqwest::Error> {
-        let mut builder = match self.method {
-            Method::Get => client.get(&self.url),
-            Method::Post => client.post(&self.url),
+macro_rules! build {
+    ($request:expr, $client:expr) => {{
+        let request = $request;
+        let mut builder = match request.method {
+            Method::Get => $client.get(&request.url),
+            Method::Post => $client.post(&request.url),
+            Method::Put => $client.put(&request.url),
+            Method::Delete => $client.delete(&request.url),
         };
 
-        if let Some(agent) = self.user_agent.clone() {
+        if let Some(agent) = request.user_agent.clone() {
             builder = builder.header(header::USER_AGENT, agent.0);
         }
 
-        if let Some(headers) = self.headers.clone() {
+        if let Some(headers) = request.headers.clone() {
             builder = builder.headers(headers);
         }
 
-        if let Some(content_type) = self.content_type {
+        if let Some(content_type) = request.content_type {
             builder = match content_type {
                 ContentType::Json => builder.header(header::CONTENT_TYPE, CONTENT_JSON),
                 ContentType::FormUrlEncoded => {
@@ -72,10 +190,46 @@ impl Request {
             };
         }
 
-        if let Some(body) = self.body.clone() {
+        if let Some(body) = request.body.clone() {
             builder = builder.body(body);
         }
 
-        builder.send()
+        builder
+    }};
+}
+
+#[cfg(feature = "client")]
+impl Request {
+    /// Sends the request and returns the response.
+    pub fn execute(&self, client: &Client) -> Result<Response, reqwest::Error> {
+        self.to_reqwest(client).send()
+    }
+
+    /// Builds the request with the blocking client, so that it may be
+    /// adjusted before it is sent, for example with extra query parameters
+    /// or authentication.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use urlshortener::providers::{self, Provider};
+    ///
+    /// let client = reqwest::blocking::Client::new();
+    /// let req = providers::request("https://rust-lang.org", &Provider::IsGd);
+    /// let res = req
+    ///     .to_reqwest(&client)
+    ///     .query(&[("logstats", "1")])
+    ///     .send()
+    ///     .unwrap();
+    /// assert!(res.status().is_success());
+    /// ```
+    pub fn to_reqwest(&self, client: &Client) -> RequestBuilder {
+        build!(self, client)
+    }
+
+    /// Builds the request with the async client, so that it may be adjusted
+    /// and sent from an async runtime.
+    pub fn to_reqwest_async(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        build!(self, client)
     }
 }
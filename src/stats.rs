@@ -0,0 +1,63 @@
+/// A time unit the link statistics are aggregated by.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StatsUnit {
+    /// Aggregate by minutes.
+    Minute,
+    /// Aggregate by hours.
+    Hour,
+    /// Aggregate by days.
+    Day,
+    /// Aggregate by weeks.
+    Week,
+    /// Aggregate by months.
+    Month,
+}
+
+impl StatsUnit {
+    /// Returns the lowercase name of the unit as used by the provider APIs.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            StatsUnit::Minute => "minute",
+            StatsUnit::Hour => "hour",
+            StatsUnit::Day => "day",
+            StatsUnit::Week => "week",
+            StatsUnit::Month => "month",
+        }
+    }
+}
+
+/// Describes the period the link statistics are collected over: the last
+/// `units` of `unit`, or the whole link lifetime if `units` is `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use urlshortener::stats::{StatsPeriod, StatsUnit};
+///
+/// // The last seven days.
+/// let period = StatsPeriod { unit: StatsUnit::Day, units: Some(7) };
+/// assert_ne!(period, StatsPeriod::default());
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StatsPeriod {
+    /// The time unit.
+    pub unit: StatsUnit,
+    /// The number of units, `None` means all the time.
+    pub units: Option<u32>,
+}
+
+impl Default for StatsPeriod {
+    fn default() -> StatsPeriod {
+        StatsPeriod {
+            unit: StatsUnit::Day,
+            units: None,
+        }
+    }
+}
+
+/// The link statistics reported by a provider.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct LinkStats {
+    /// The total number of clicks over the requested period.
+    pub clicks: u64,
+}